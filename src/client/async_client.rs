@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::{pyclass, pymethods, Bound, PyAny, PyResult, Python};
+use pyo3_asyncio_0_21::tokio::future_into_py;
+
+use crate::client::boltz::BoltzApi;
+use crate::client::config::ClientConfig;
+use crate::utils::errors::to_python_error;
+
+async fn run_blocking<T, F>(f: F) -> PyResult<T>
+where
+    F: FnOnce() -> PyResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(res) => res,
+        Err(err) => Err(to_python_error::<PyRuntimeError, _>("request task failed", err)),
+    }
+}
+
+#[pyclass]
+pub struct AsyncClient {
+    api: Arc<BoltzApi>,
+}
+
+#[pymethods]
+impl AsyncClient {
+    #[new]
+    #[pyo3(signature = (base_url, referral_id=None, config=None))]
+    pub fn new(base_url: String, referral_id: Option<String>, config: Option<ClientConfig>) -> PyResult<Self> {
+        Ok(AsyncClient {
+            api: Arc::new(BoltzApi::new(base_url, referral_id, config)?),
+        })
+    }
+
+    pub fn create_submarine_swap<'py>(
+        &self,
+        py: Python<'py>,
+        from: String,
+        to: String,
+        invoice: String,
+        refund_public_key: Vec<u8>,
+        pair_hash: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || {
+            api.create_submarine_swap(from, to, invoice, refund_public_key, pair_hash)
+        }))
+    }
+
+    pub fn create_reverse_swap<'py>(
+        &self,
+        py: Python<'py>,
+        from: String,
+        to: String,
+        preimage_hash: Vec<u8>,
+        claim_public_key: Vec<u8>,
+        invoice_amount: Option<u64>,
+        onchain_amount: Option<u64>,
+        description: Option<String>,
+        address: Option<String>,
+        address_signature: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || {
+            api.create_reverse_swap(
+                from,
+                to,
+                preimage_hash,
+                claim_public_key,
+                invoice_amount,
+                onchain_amount,
+                description,
+                address,
+                address_signature,
+            )
+        }))
+    }
+
+    pub fn create_chain_swap<'py>(
+        &self,
+        py: Python<'py>,
+        from: String,
+        to: String,
+        preimage_hash: Vec<u8>,
+        claim_public_key: Vec<u8>,
+        refund_public_key: Vec<u8>,
+        user_lock_amount: Option<u64>,
+        server_lock_amount: Option<u64>,
+        pair_hash: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || {
+            api.create_chain_swap(
+                from,
+                to,
+                preimage_hash,
+                claim_public_key,
+                refund_public_key,
+                user_lock_amount,
+                server_lock_amount,
+                pair_hash,
+            )
+        }))
+    }
+
+    pub fn get_submarine_claim_details<'py>(&self, py: Python<'py>, swap_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.get_submarine_claim_details(swap_id)))
+    }
+
+    pub fn get_submarine_pairs<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.get_submarine_pairs()))
+    }
+
+    pub fn get_reverse_pairs<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.get_reverse_pairs()))
+    }
+
+    pub fn get_chain_pairs<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.get_chain_pairs()))
+    }
+
+    pub fn get_swap_status<'py>(&self, py: Python<'py>, swap_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.get_swap_status(swap_id)))
+    }
+
+    pub fn subscribe_swap_status<'py>(&self, py: Python<'py>, swap_ids: Vec<String>) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.subscribe_swap_status(swap_ids)))
+    }
+
+    pub fn get_height<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.get_height()))
+    }
+
+    pub fn get_swap_transaction<'py>(&self, py: Python<'py>, swap_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.get_swap_transaction(swap_id)))
+    }
+
+    pub fn get_submarine_transaction<'py>(&self, py: Python<'py>, swap_id: String) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.get_submarine_transaction(swap_id)))
+    }
+
+    pub fn broadcast_transaction<'py>(&self, py: Python<'py>, currency: String, tx_hex: String) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.broadcast_transaction(currency, tx_hex)))
+    }
+
+    pub fn get_transaction<'py>(&self, py: Python<'py>, currency: String, txid: String) -> PyResult<Bound<'py, PyAny>> {
+        let api = self.api.clone();
+        future_into_py(py, run_blocking(move || api.get_transaction(currency, txid)))
+    }
+}
@@ -0,0 +1,48 @@
+use pyo3::{FromPyObject, PyRef, PyResult};
+
+use crate::client::bitcoind::BitcoindRpcBackend;
+use crate::client::electrum::ElectrumClient;
+use crate::client::esplora::EsploraClient;
+use crate::types::onchain::{ScriptHistoryItem, Utxo};
+use crate::utils::errors::{new_python_error, BoltzError};
+
+pub(crate) trait ChainBackend: Send + Sync {
+    fn is_liquid(&self) -> bool;
+
+    fn script_history(&self, script: &[u8]) -> PyResult<Vec<ScriptHistoryItem>>;
+
+    fn utxos(&self, script: &[u8]) -> PyResult<Vec<Utxo>>;
+
+    fn transaction(&self, txid: &str) -> PyResult<Vec<u8>>;
+
+    fn broadcast(&self, tx: &[u8]) -> PyResult<String>;
+
+    fn tip_height(&self) -> PyResult<u32>;
+
+    // fee rate in sat/vbyte
+    fn estimate_fee(&self, blocks: usize) -> PyResult<f64>;
+
+    fn lockup_transaction(&self, script: &[u8]) -> PyResult<Vec<u8>> {
+        match self.utxos(script)?.first() {
+            Some(utxo) => self.transaction(utxo.txid.as_str()),
+            None => Err(new_python_error::<BoltzError>("lockup address is not funded")),
+        }
+    }
+}
+
+#[derive(FromPyObject)]
+pub(crate) enum Backend<'py> {
+    Electrum(PyRef<'py, ElectrumClient>),
+    Esplora(PyRef<'py, EsploraClient>),
+    Bitcoind(PyRef<'py, BitcoindRpcBackend>),
+}
+
+impl Backend<'_> {
+    pub fn get(&self) -> &dyn ChainBackend {
+        match self {
+            Backend::Electrum(client) => &**client,
+            Backend::Esplora(client) => &**client,
+            Backend::Bitcoind(client) => &**client,
+        }
+    }
+}
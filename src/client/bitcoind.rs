@@ -0,0 +1,364 @@
+use std::collections::HashSet;
+use std::fs;
+
+use bitcoin::base64::engine::general_purpose::STANDARD;
+use bitcoin::base64::Engine;
+use bitcoin::{Address, Network, Script};
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, PyResult, Python};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::client::backend::ChainBackend;
+use crate::client::config::ClientConfig;
+use crate::client::http::HttpTransport;
+use crate::types::onchain::{ScriptHistoryItem, Utxo};
+use crate::utils::address::address_script_pubkey;
+use crate::utils::errors::{api_error_body, api_error_status, handle_rust_error, handle_rust_error_as, new_python_error, ApiError, BoltzError, NetworkError};
+use crate::utils::network::INVALID_NETWORK;
+
+// listtransactions is paged, this is plenty for the handful of swap scripts
+// a watch-only wallet usually tracks
+const LIST_TRANSACTIONS_COUNT: u32 = 10_000;
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UnspentOutput {
+    txid: String,
+    vout: u32,
+    script_pub_key: String,
+    amount: f64,
+    #[serde(default)]
+    confirmations: u32,
+    height: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ScanResult {
+    unspents: Vec<UnspentOutput>,
+}
+
+#[derive(Deserialize)]
+struct WalletTransaction {
+    txid: String,
+    address: Option<String>,
+    blockheight: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct FeeEstimate {
+    feerate: Option<f64>,
+}
+
+fn parse_network(network: &str) -> PyResult<Network> {
+    match network {
+        "main" => Ok(Network::Bitcoin),
+        "testnet" => Ok(Network::Testnet),
+        "regtest" => Ok(Network::Regtest),
+        _ => Err(PyValueError::new_err(INVALID_NETWORK)),
+    }
+}
+
+fn default_port(network: Network) -> u16 {
+    match network {
+        Network::Bitcoin => 8332,
+        Network::Testnet => 18332,
+        _ => 18443,
+    }
+}
+
+fn basic_auth(credentials: &str) -> String {
+    format!("Basic {}", STANDARD.encode(credentials))
+}
+
+fn read_cookie(cookie_file: &str) -> PyResult<String> {
+    let cookie = handle_rust_error_as::<NetworkError, _, _>("could not read cookie file", fs::read_to_string(cookie_file))?;
+    Ok(cookie.trim().to_string())
+}
+
+fn to_sat(amount: f64) -> u64 {
+    (amount * 100_000_000.0).round() as u64
+}
+
+#[pyclass]
+pub struct BitcoindRpcBackend {
+    http: HttpTransport,
+    network: Network,
+    wallet: Option<String>,
+    cookie_file: Option<String>,
+}
+
+impl BitcoindRpcBackend {
+    // bitcoind answers failed calls with an HTTP error status and the RPC
+    // error in the body
+    fn post<T: DeserializeOwned>(&self, msg: &str, path: &str, body: &Value) -> PyResult<RpcResponse<T>> {
+        match self.http.post(msg, path, body) {
+            Err(err) => match api_error_body(&err).and_then(|body| serde_json::from_str::<RpcResponse<Value>>(&body).ok()) {
+                Some(RpcResponse { error: Some(error), .. }) => Ok(RpcResponse { result: None, error: Some(error) }),
+                _ => Err(err),
+            },
+            res => res,
+        }
+    }
+
+    fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> PyResult<T> {
+        let msg = format!("bitcoind {} failed", method);
+        let path = match &self.wallet {
+            Some(wallet) => format!("/wallet/{}", wallet),
+            None => String::new(),
+        };
+
+        let body = json!({ "jsonrpc": "1.0", "id": "boltz", "method": method, "params": params });
+
+        // bitcoind writes a new cookie on every restart, so it is read again
+        // once the old one is rejected
+        let res: RpcResponse<T> = match (self.post(msg.as_str(), path.as_str(), &body), &self.cookie_file) {
+            (Err(err), Some(cookie_file)) if api_error_status(&err) == Some(401) => {
+                let cookie = read_cookie(cookie_file)?;
+                self.http.set_header("Authorization", basic_auth(cookie.as_str()));
+                self.post(msg.as_str(), path.as_str(), &body)?
+            }
+            (res, _) => res?,
+        };
+        if let Some(err) = res.error {
+            return Err(new_python_error::<ApiError>(&format!("{}: {} ({})", msg, err.message, err.code)));
+        }
+
+        res.result.ok_or_else(|| new_python_error::<ApiError>(&format!("{}: empty result", msg)))
+    }
+
+    fn require_wallet(&self) -> PyResult<()> {
+        match self.wallet {
+            Some(_) => Ok(()),
+            None => Err(new_python_error::<BoltzError>("a wallet is required to watch scripts")),
+        }
+    }
+
+    fn script_address(&self, script: &[u8]) -> PyResult<String> {
+        let address = handle_rust_error(
+            "script has no address",
+            Address::from_script(Script::from_bytes(script), self.network),
+        )?;
+
+        Ok(address.to_string())
+    }
+
+    fn descriptor(&self, script: &[u8]) -> PyResult<String> {
+        let descriptor = format!("raw({})", hex::encode(script));
+        let info: Value = self.call("getdescriptorinfo", json!([descriptor]))?;
+        let checksum = info["checksum"]
+            .as_str()
+            .ok_or_else(|| new_python_error::<ApiError>("bitcoind getdescriptorinfo failed: no checksum"))?;
+
+        Ok(format!("{}#{}", descriptor, checksum))
+    }
+
+    fn watch(&self, script: &[u8], rescan: bool) -> PyResult<()> {
+        self.require_wallet()?;
+
+        let timestamp = if rescan { json!(0) } else { json!("now") };
+        let results: Vec<Value> = self.call(
+            "importdescriptors",
+            json!([[{ "desc": self.descriptor(script)?, "timestamp": timestamp, "label": "boltz" }]]),
+        )?;
+        match results.first() {
+            Some(result) if result["success"].as_bool() == Some(true) => Ok(()),
+            Some(result) => Err(new_python_error::<ApiError>(&format!(
+                "bitcoind importdescriptors failed: {}",
+                result["error"]
+            ))),
+            None => Err(new_python_error::<ApiError>("bitcoind importdescriptors failed: empty result")),
+        }
+    }
+}
+
+impl ChainBackend for BitcoindRpcBackend {
+    fn is_liquid(&self) -> bool {
+        false
+    }
+
+    // Only wallets keep a transaction history, so the script has to be
+    // watched before it is funded
+    fn script_history(&self, script: &[u8]) -> PyResult<Vec<ScriptHistoryItem>> {
+        self.require_wallet()?;
+        let address = self.script_address(script)?;
+
+        let txs: Vec<WalletTransaction> = self.call("listtransactions", json!(["*", LIST_TRANSACTIONS_COUNT, 0, true]))?;
+        let mut seen = HashSet::new();
+
+        Ok(txs
+            .into_iter()
+            .filter(|tx| tx.address.as_deref() == Some(address.as_str()))
+            .filter(|tx| seen.insert(tx.txid.clone()))
+            .map(|tx| ScriptHistoryItem {
+                txid: tx.txid,
+                height: tx.blockheight,
+            })
+            .collect())
+    }
+
+    // With a wallet the watched outputs are listed including the mempool,
+    // without one the UTXO set is scanned which only has confirmed outputs
+    fn utxos(&self, script: &[u8]) -> PyResult<Vec<Utxo>> {
+        let script_hex = hex::encode(script);
+
+        let unspent = match self.wallet {
+            Some(_) => {
+                let tip = self.tip_height()?;
+                let unspent: Vec<UnspentOutput> = self.call("listunspent", json!([0, 9_999_999, [], true]))?;
+                unspent
+                    .into_iter()
+                    .map(|utxo| UnspentOutput {
+                        height: (utxo.confirmations > 0).then(|| tip.saturating_add(1).saturating_sub(utxo.confirmations)),
+                        ..utxo
+                    })
+                    .collect()
+            }
+            None => {
+                let scan: ScanResult = self.call("scantxoutset", json!(["start", [format!("raw({})", script_hex)]]))?;
+                scan.unspents
+            }
+        };
+
+        Ok(unspent
+            .into_iter()
+            .filter(|utxo| utxo.script_pub_key == script_hex)
+            .map(|utxo| Utxo {
+                value: Some(to_sat(utxo.amount)),
+                txid: utxo.txid,
+                vout: utxo.vout,
+                height: utxo.height,
+            })
+            .collect())
+    }
+
+    fn transaction(&self, txid: &str) -> PyResult<Vec<u8>> {
+        // getrawtransaction needs -txindex for confirmed transactions that
+        // are not in the wallet
+        let tx_hex = match self.call::<String>("getrawtransaction", json!([txid, false])) {
+            Ok(tx_hex) => tx_hex,
+            Err(err) if self.wallet.is_some() => {
+                let tx: Value = self.call("gettransaction", json!([txid, true])).map_err(|_| err)?;
+                tx["hex"].as_str().unwrap_or_default().to_string()
+            }
+            Err(err) => return Err(err),
+        };
+
+        handle_rust_error("could not decode transaction hex", hex::decode(tx_hex))
+    }
+
+    fn broadcast(&self, tx: &[u8]) -> PyResult<String> {
+        self.call("sendrawtransaction", json!([hex::encode(tx)]))
+    }
+
+    fn tip_height(&self) -> PyResult<u32> {
+        self.call("getblockcount", json!([]))
+    }
+
+    // bitcoind reports BTC per kvB
+    fn estimate_fee(&self, blocks: usize) -> PyResult<f64> {
+        let estimate: FeeEstimate = self.call("estimatesmartfee", json!([blocks]))?;
+        match estimate.feerate {
+            Some(fee) => Ok(fee * 100_000.0),
+            None => Err(new_python_error::<NetworkError>("bitcoind has no fee estimate")),
+        }
+    }
+}
+
+#[pymethods]
+impl BitcoindRpcBackend {
+    #[new]
+    #[pyo3(signature = (network, url=None, wallet=None, cookie_file=None, user=None, password=None, config=None))]
+    pub fn new(
+        network: String,
+        url: Option<String>,
+        wallet: Option<String>,
+        cookie_file: Option<String>,
+        user: Option<String>,
+        password: Option<String>,
+        config: Option<ClientConfig>,
+    ) -> PyResult<Self> {
+        let network = parse_network(network.as_str())?;
+        let url = url.unwrap_or_else(|| format!("http://127.0.0.1:{}", default_port(network)));
+
+        let credentials = match (&cookie_file, user, password) {
+            (Some(cookie_file), None, None) => Some(read_cookie(cookie_file)?),
+            (None, Some(user), Some(password)) => Some(format!("{}:{}", user, password)),
+            (None, None, None) => None,
+            _ => return Err(PyValueError::new_err("either cookie_file or user and password have to be set")),
+        };
+
+        let mut config = config.unwrap_or_default();
+        if let Some(credentials) = credentials {
+            config.headers.insert("Authorization".to_string(), basic_auth(credentials.as_str()));
+        }
+
+        Ok(BitcoindRpcBackend {
+            http: HttpTransport::new(url, &config)?,
+            network,
+            wallet,
+            cookie_file,
+        })
+    }
+
+    #[getter]
+    pub fn url(&self) -> String {
+        self.http.base_url().to_string()
+    }
+
+    #[getter]
+    pub fn wallet(&self) -> Option<String> {
+        self.wallet.clone()
+    }
+
+    #[pyo3(signature = (script, rescan=false))]
+    pub fn watch_script(&self, py: Python, script: Vec<u8>, rescan: bool) -> PyResult<()> {
+        py.allow_threads(|| self.watch(script.as_slice(), rescan))
+    }
+
+    pub fn get_script_history(&self, py: Python, script: Vec<u8>) -> PyResult<Vec<ScriptHistoryItem>> {
+        py.allow_threads(|| self.script_history(script.as_slice()))
+    }
+
+    pub fn get_address_history(&self, py: Python, address: String) -> PyResult<Vec<ScriptHistoryItem>> {
+        let script = address_script_pubkey("BTC", address.as_str())?;
+        py.allow_threads(|| self.script_history(script.as_slice()))
+    }
+
+    pub fn get_utxos(&self, py: Python, script: Vec<u8>) -> PyResult<Vec<Utxo>> {
+        py.allow_threads(|| self.utxos(script.as_slice()))
+    }
+
+    pub fn get_transaction(&self, py: Python, txid: String) -> PyResult<String> {
+        py.allow_threads(|| self.transaction(txid.as_str()).map(hex::encode))
+    }
+
+    #[pyo3(name = "broadcast")]
+    pub fn broadcast_hex(&self, py: Python, tx_hex: String) -> PyResult<String> {
+        let tx = handle_rust_error("could not decode transaction hex", hex::decode(tx_hex))?;
+        py.allow_threads(|| self.broadcast(tx.as_slice()))
+    }
+
+    pub fn get_tip_height(&self, py: Python) -> PyResult<u32> {
+        py.allow_threads(|| self.tip_height())
+    }
+
+    // sat per vbyte
+    #[pyo3(name = "estimate_fee", signature = (blocks=2))]
+    pub fn estimate_fee_rate(&self, py: Python, blocks: usize) -> PyResult<f64> {
+        py.allow_threads(|| self.estimate_fee(blocks))
+    }
+}
@@ -1,27 +1,67 @@
-use boltz_client::boltz::CreateSubmarineRequest;
-use boltz_client::boltz::BoltzApiClientV2 as BoltzApiClient;
-use pyo3::{pyclass, pymethods, PyResult};
+use std::sync::Arc;
 
-use crate::types::client::{GetSubmarinePairsResponse, HeightResponse};
-use crate::types::submarine::CreateSubmarineResponse;
-use crate::utils::errors::handle_rust_error;
+use bitcoin::hashes::{sha256, Hash};
+use boltz_client::boltz::{self, CreateChainRequest, CreateReverseRequest, CreateSubmarineRequest, SwapTxKind, SwapType};
+use boltz_client::elements::secp256k1_zkp::{MusigPartialSignature, MusigPubNonce};
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, PyErr, PyResult, Python};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::client::config::ClientConfig;
+use crate::client::http::HttpTransport;
+use crate::client::ws::{ws_url, SwapStatusStream};
+use crate::types::chain::CreateChainResponse;
+use crate::types::client::{
+    GetChainPairsResponse, GetReversePairsResponse, GetSubmarinePairsResponse, HeightResponse,
+};
+use crate::types::reverse::CreateReverseResponse;
+use crate::types::status::{SwapUpdate, SwapUpdateRaw};
+use crate::types::submarine::{CreateSubmarineResponse, SubmarineClaimDetails};
+use crate::types::transaction::{
+    ChainTransactionsResponse, LockupTransactionResponse, SubmarineTransaction, SwapTransaction,
+};
+use crate::utils::errors::{api_error_body, api_error_status, handle_rust_error, new_python_error, ApiError};
 use crate::utils::keys::parse_public_key;
 
-#[pyclass]
-pub struct Client {
-    client: BoltzApiClient,
+#[derive(Deserialize)]
+struct TransactionIdResponse {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct TransactionHexResponse {
+    hex: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PartialSignatureResponse {
+    pub pub_nonce: String,
+    pub partial_signature: String,
+}
+
+// Boltz answers 404 for unknown swap IDs and a 400 naming the swap type for
+// swaps of another type
+fn is_not_reverse_swap(err: &PyErr) -> bool {
+    match api_error_status(err) {
+        Some(404) => true,
+        Some(400) => api_error_body(err).is_some_and(|body| body.to_lowercase().contains("not a reverse swap")),
+        _ => false,
+    }
+}
+
+pub(crate) struct BoltzApi {
+    http: HttpTransport,
     referral_id: Option<String>,
 }
 
-#[pymethods]
-impl Client {
-    #[new]
-    pub fn new(base_url: String, referral_id: Option<String>) -> Self {
-        Client {
+impl BoltzApi {
+    pub fn new(base_url: String, referral_id: Option<String>, config: Option<ClientConfig>) -> PyResult<Self> {
+        Ok(BoltzApi {
             referral_id,
-            client: BoltzApiClient::new(&base_url),
-        }
-
+            http: HttpTransport::new(base_url, &config.unwrap_or_default())?,
+        })
     }
 
     pub fn create_submarine_swap(
@@ -32,30 +72,387 @@ impl Client {
         refund_public_key: Vec<u8>,
         pair_hash: Option<String>,
     ) -> PyResult<CreateSubmarineResponse> {
-        let res = handle_rust_error(
+        let res: boltz::CreateSubmarineResponse = self.http.post(
             "could not create submarine swap",
-            self.client.post_swap_req(&CreateSubmarineRequest {
+            "/swap/submarine",
+            &CreateSubmarineRequest {
                 to,
                 from,
                 invoice,
                 pair_hash,
                 referral_id: self.referral_id.clone(),
                 refund_public_key: parse_public_key(refund_public_key)?,
-            }),
+            },
+        )?;
+
+        Ok(res.into())
+    }
+
+    pub fn create_reverse_swap(
+        &self,
+        from: String,
+        to: String,
+        preimage_hash: Vec<u8>,
+        claim_public_key: Vec<u8>,
+        invoice_amount: Option<u64>,
+        onchain_amount: Option<u64>,
+        description: Option<String>,
+        address: Option<String>,
+        address_signature: Option<String>,
+    ) -> PyResult<CreateReverseResponse> {
+        let preimage_hash = handle_rust_error(
+            "could not parse preimage hash",
+            sha256::Hash::from_slice(preimage_hash.as_slice()),
+        )?;
+        let res: boltz::CreateReverseResponse = self.http.post(
+            "could not create reverse swap",
+            "/swap/reverse",
+            &CreateReverseRequest {
+                from,
+                to,
+                invoice_amount,
+                onchain_amount,
+                preimage_hash,
+                description,
+                address,
+                address_signature,
+                referral_id: self.referral_id.clone(),
+                claim_public_key: parse_public_key(claim_public_key)?,
+            },
+        )?;
+
+        Ok(res.into())
+    }
+
+    pub fn create_chain_swap(
+        &self,
+        from: String,
+        to: String,
+        preimage_hash: Vec<u8>,
+        claim_public_key: Vec<u8>,
+        refund_public_key: Vec<u8>,
+        user_lock_amount: Option<u64>,
+        server_lock_amount: Option<u64>,
+        pair_hash: Option<String>,
+    ) -> PyResult<CreateChainResponse> {
+        let preimage_hash = handle_rust_error(
+            "could not parse preimage hash",
+            sha256::Hash::from_slice(preimage_hash.as_slice()),
+        )?;
+        let res: boltz::CreateChainResponse = self.http.post(
+            "could not create chain swap",
+            "/swap/chain",
+            &CreateChainRequest {
+                from,
+                to,
+                preimage_hash,
+                user_lock_amount,
+                server_lock_amount,
+                pair_hash,
+                referral_id: self.referral_id.clone(),
+                claim_public_key: Some(parse_public_key(claim_public_key)?),
+                refund_public_key: Some(parse_public_key(refund_public_key)?),
+            },
+        )?;
+
+        Ok(res.into())
+    }
+
+    pub fn get_submarine_claim_details(&self, swap_id: String) -> PyResult<SubmarineClaimDetails> {
+        let res: boltz::SubmarineClaimTxResponse = self.http.get(
+            "could not fetch submarine claim details",
+            &format!("/swap/submarine/{}/claim", swap_id),
         )?;
 
         Ok(res.into())
     }
 
+    pub fn post_submarine_claim_signature(
+        &self,
+        swap_id: &str,
+        pub_nonce: MusigPubNonce,
+        partial_sig: MusigPartialSignature,
+    ) -> PyResult<()> {
+        let _: Value = self.http.post(
+            "could not send partial signature",
+            &format!("/swap/submarine/{}/claim", swap_id),
+            &json!({
+                "pubNonce": hex::encode(pub_nonce.serialize()),
+                "partialSignature": hex::encode(partial_sig.serialize()),
+            }),
+        )?;
+
+        Ok(())
+    }
+
+    // Partial signature of Boltz for the cooperative key-path spend of the
+    // first input of transaction; claims also reveal the preimage
+    pub fn get_partial_signature(
+        &self,
+        kind: &SwapTxKind,
+        swap_type: &SwapType,
+        swap_id: &str,
+        pub_nonce: String,
+        transaction: String,
+        preimage: Option<String>,
+    ) -> PyResult<PartialSignatureResponse> {
+        let to_sign = json!({ "index": 0, "transaction": transaction, "pubNonce": pub_nonce });
+        let (path, body) = match (kind, swap_type) {
+            (SwapTxKind::Claim, SwapType::ReverseSubmarine) => {
+                let mut body = to_sign;
+                body["preimage"] = json!(preimage);
+                (format!("/swap/reverse/{}/claim", swap_id), body)
+            }
+            (SwapTxKind::Claim, SwapType::Chain) => (
+                format!("/swap/chain/{}/claim", swap_id),
+                json!({ "preimage": preimage, "toSign": to_sign }),
+            ),
+            (SwapTxKind::Refund, SwapType::Submarine) => (format!("/swap/submarine/{}/refund", swap_id), to_sign),
+            (SwapTxKind::Refund, SwapType::Chain) => (format!("/swap/chain/{}/refund", swap_id), to_sign),
+            _ => return Err(PyValueError::new_err("swap cannot be spent cooperatively")),
+        };
+
+        self.http.post("could not fetch partial signature", &path, &body)
+    }
+
     pub fn get_submarine_pairs(&self) -> PyResult<GetSubmarinePairsResponse> {
-        let res = handle_rust_error("could not fetch pairs", self.client.get_submarine_pairs())?;
+        let res: boltz::GetSubmarinePairsResponse = self.http.get("could not fetch pairs", "/swap/submarine")?;
+
+        Ok(res.into())
+    }
+
+    pub fn get_reverse_pairs(&self) -> PyResult<GetReversePairsResponse> {
+        let res: boltz::GetReversePairsResponse = self.http.get("could not fetch reverse pairs", "/swap/reverse")?;
 
         Ok(res.into())
     }
 
+    pub fn get_chain_pairs(&self) -> PyResult<GetChainPairsResponse> {
+        let res: boltz::GetChainPairsResponse = self.http.get("could not fetch chain pairs", "/swap/chain")?;
+
+        Ok(res.into())
+    }
+
+    pub fn get_swap_status(&self, swap_id: String) -> PyResult<SwapUpdate> {
+        let mut raw: SwapUpdateRaw = self.http.get("could not fetch swap status", &format!("/swap/{}", swap_id))?;
+        raw.id = swap_id;
+
+        Ok(raw.into())
+    }
+
+    pub fn subscribe_swap_status(&self, swap_ids: Vec<String>) -> PyResult<SwapStatusStream> {
+        SwapStatusStream::new(ws_url(self.http.base_url()), swap_ids)
+    }
+
     pub fn get_height(&self) -> PyResult<HeightResponse> {
-        let res = handle_rust_error("could not fetch height", self.client.get_height())?;
+        let res: boltz::HeightResponse = self.http.get("could not fetch height", "/chain/heights")?;
 
         Ok(res.into())
     }
+
+    // The swap ID does not tell whether it is a reverse or a chain swap, so
+    // the chain swap endpoint is tried when Boltz knows no such reverse swap.
+    // Network and server errors are returned as they are
+    pub fn get_swap_transaction(&self, swap_id: String) -> PyResult<SwapTransaction> {
+        let reverse: PyResult<LockupTransactionResponse> = self.http.get(
+            "could not fetch swap transaction",
+            &format!("/swap/reverse/{}/transaction", swap_id),
+        );
+        let err = match reverse {
+            Ok(res) => return Ok(res.into()),
+            Err(err) if is_not_reverse_swap(&err) => err,
+            Err(err) => return Err(err),
+        };
+
+        let chain: ChainTransactionsResponse = match self.http.get(
+            "could not fetch swap transaction",
+            &format!("/swap/chain/{}/transactions", swap_id),
+        ) {
+            Ok(res) => res,
+            Err(_) => return Err(err),
+        };
+        match chain.server_lock {
+            Some(lockup) => Ok(lockup.into()),
+            None => Err(new_python_error::<ApiError>("could not fetch swap transaction: server did not lock up yet")),
+        }
+    }
+
+    pub fn get_submarine_transaction(&self, swap_id: String) -> PyResult<SubmarineTransaction> {
+        let res: LockupTransactionResponse = self.http.get(
+            "could not fetch submarine transaction",
+            &format!("/swap/submarine/{}/transaction", swap_id),
+        )?;
+
+        Ok(res.into())
+    }
+
+    pub fn broadcast_transaction(&self, currency: String, tx_hex: String) -> PyResult<String> {
+        let res: TransactionIdResponse = self.http.post(
+            "could not broadcast transaction",
+            &format!("/chain/{}/transaction", currency),
+            &json!({ "hex": tx_hex }),
+        )?;
+
+        Ok(res.id)
+    }
+
+    // Swaps whose keys derive from the xpub of a rescue file
+    pub(crate) fn restore_swaps(&self, xpub: String) -> PyResult<Vec<Value>> {
+        self.http.post("could not restore swaps", "/swap/restore", &json!({ "xpub": xpub }))
+    }
+
+    pub fn get_transaction(&self, currency: String, txid: String) -> PyResult<String> {
+        let res: TransactionHexResponse = self.http.get(
+            "could not fetch transaction",
+            &format!("/chain/{}/transaction/{}", currency, txid),
+        )?;
+
+        Ok(res.hex)
+    }
+}
+
+#[pyclass]
+pub struct Client {
+    api: Arc<BoltzApi>,
+}
+
+impl Client {
+    pub(crate) fn boltz(&self) -> &BoltzApi {
+        &self.api
+    }
+}
+
+#[pymethods]
+impl Client {
+    #[new]
+    #[pyo3(signature = (base_url, referral_id=None, config=None))]
+    pub fn new(base_url: String, referral_id: Option<String>, config: Option<ClientConfig>) -> PyResult<Self> {
+        Ok(Client {
+            api: Arc::new(BoltzApi::new(base_url, referral_id, config)?),
+        })
+    }
+
+    pub fn create_submarine_swap(
+        &self,
+        py: Python,
+        from: String,
+        to: String,
+        invoice: String,
+        refund_public_key: Vec<u8>,
+        pair_hash: Option<String>,
+    ) -> PyResult<CreateSubmarineResponse> {
+        let api = &self.api;
+        py.allow_threads(|| api.create_submarine_swap(from, to, invoice, refund_public_key, pair_hash))
+    }
+
+    pub fn create_reverse_swap(
+        &self,
+        py: Python,
+        from: String,
+        to: String,
+        preimage_hash: Vec<u8>,
+        claim_public_key: Vec<u8>,
+        invoice_amount: Option<u64>,
+        onchain_amount: Option<u64>,
+        description: Option<String>,
+        address: Option<String>,
+        address_signature: Option<String>,
+    ) -> PyResult<CreateReverseResponse> {
+        let api = &self.api;
+        py.allow_threads(|| {
+            api.create_reverse_swap(
+                from,
+                to,
+                preimage_hash,
+                claim_public_key,
+                invoice_amount,
+                onchain_amount,
+                description,
+                address,
+                address_signature,
+            )
+        })
+    }
+
+    pub fn create_chain_swap(
+        &self,
+        py: Python,
+        from: String,
+        to: String,
+        preimage_hash: Vec<u8>,
+        claim_public_key: Vec<u8>,
+        refund_public_key: Vec<u8>,
+        user_lock_amount: Option<u64>,
+        server_lock_amount: Option<u64>,
+        pair_hash: Option<String>,
+    ) -> PyResult<CreateChainResponse> {
+        let api = &self.api;
+        py.allow_threads(|| {
+            api.create_chain_swap(
+                from,
+                to,
+                preimage_hash,
+                claim_public_key,
+                refund_public_key,
+                user_lock_amount,
+                server_lock_amount,
+                pair_hash,
+            )
+        })
+    }
+
+    pub fn get_submarine_claim_details(&self, py: Python, swap_id: String) -> PyResult<SubmarineClaimDetails> {
+        let api = &self.api;
+        py.allow_threads(|| api.get_submarine_claim_details(swap_id))
+    }
+
+    pub fn get_submarine_pairs(&self, py: Python) -> PyResult<GetSubmarinePairsResponse> {
+        let api = &self.api;
+        py.allow_threads(|| api.get_submarine_pairs())
+    }
+
+    pub fn get_reverse_pairs(&self, py: Python) -> PyResult<GetReversePairsResponse> {
+        let api = &self.api;
+        py.allow_threads(|| api.get_reverse_pairs())
+    }
+
+    pub fn get_chain_pairs(&self, py: Python) -> PyResult<GetChainPairsResponse> {
+        let api = &self.api;
+        py.allow_threads(|| api.get_chain_pairs())
+    }
+
+    pub fn get_swap_status(&self, py: Python, swap_id: String) -> PyResult<SwapUpdate> {
+        let api = &self.api;
+        py.allow_threads(|| api.get_swap_status(swap_id))
+    }
+
+    pub fn subscribe_swap_status(&self, py: Python, swap_ids: Vec<String>) -> PyResult<SwapStatusStream> {
+        let api = &self.api;
+        py.allow_threads(|| api.subscribe_swap_status(swap_ids))
+    }
+
+    pub fn get_height(&self, py: Python) -> PyResult<HeightResponse> {
+        let api = &self.api;
+        py.allow_threads(|| api.get_height())
+    }
+
+    pub fn get_swap_transaction(&self, py: Python, swap_id: String) -> PyResult<SwapTransaction> {
+        let api = &self.api;
+        py.allow_threads(|| api.get_swap_transaction(swap_id))
+    }
+
+    pub fn get_submarine_transaction(&self, py: Python, swap_id: String) -> PyResult<SubmarineTransaction> {
+        let api = &self.api;
+        py.allow_threads(|| api.get_submarine_transaction(swap_id))
+    }
+
+    pub fn broadcast_transaction(&self, py: Python, currency: String, tx_hex: String) -> PyResult<String> {
+        let api = &self.api;
+        py.allow_threads(|| api.broadcast_transaction(currency, tx_hex))
+    }
+
+    pub fn get_transaction(&self, py: Python, currency: String, txid: String) -> PyResult<String> {
+        let api = &self.api;
+        py.allow_threads(|| api.get_transaction(currency, txid))
+    }
 }
@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::PyDictMethods;
+use pyo3::types::PyDict;
+use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    #[pyo3(get, set)]
+    pub connect_timeout: Option<f64>,
+    #[pyo3(get, set)]
+    pub read_timeout: Option<f64>,
+    #[pyo3(get, set)]
+    pub retries: u32,
+    #[pyo3(get, set)]
+    pub retry_backoff: f64,
+    #[pyo3(get, set)]
+    pub proxy: Option<String>,
+    #[pyo3(get, set)]
+    pub headers: HashMap<String, String>,
+    #[pyo3(get, set)]
+    pub user_agent: Option<String>,
+    #[pyo3(get, set)]
+    pub root_certs: Vec<String>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            connect_timeout: Some(10.0),
+            read_timeout: Some(30.0),
+            retries: 3,
+            retry_backoff: 0.5,
+            proxy: None,
+            headers: HashMap::new(),
+            user_agent: None,
+            root_certs: Vec::new(),
+        }
+    }
+}
+
+#[pymethods]
+impl ClientConfig {
+    #[new]
+    #[pyo3(signature = (
+        connect_timeout=Some(10.0),
+        read_timeout=Some(30.0),
+        retries=3,
+        retry_backoff=0.5,
+        proxy=None,
+        headers=None,
+        user_agent=None,
+        root_certs=None,
+    ))]
+    pub fn new(
+        connect_timeout: Option<f64>,
+        read_timeout: Option<f64>,
+        retries: u32,
+        retry_backoff: f64,
+        proxy: Option<String>,
+        headers: Option<HashMap<String, String>>,
+        user_agent: Option<String>,
+        root_certs: Option<Vec<String>>,
+    ) -> Self {
+        ClientConfig {
+            connect_timeout,
+            read_timeout,
+            retries,
+            retry_backoff,
+            proxy,
+            headers: headers.unwrap_or_default(),
+            user_agent,
+            root_certs: root_certs.unwrap_or_default(),
+        }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("connect_timeout", self.connect_timeout)?;
+        dict.set_item("read_timeout", self.read_timeout)?;
+        dict.set_item("retries", self.retries)?;
+        dict.set_item("retry_backoff", self.retry_backoff)?;
+        dict.set_item("proxy", self.proxy.clone())?;
+        dict.set_item("headers", self.headers.clone())?;
+        dict.set_item("user_agent", self.user_agent.clone())?;
+        dict.set_item("root_certs", self.root_certs.clone())?;
+        Ok(dict)
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use bitcoin::{Script, Txid};
+use boltz_client::elements;
+use boltz_client::elements::encode::deserialize;
+use electrum_client::{ConfigBuilder, ElectrumApi};
+use pyo3::prelude::PyDictMethods;
+use pyo3::types::PyDict;
+use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
+
+use crate::client::backend::ChainBackend;
+use crate::types::onchain::{ScriptHistoryItem, Utxo};
+use crate::utils::address::address_script_pubkey;
+use crate::utils::errors::{handle_rust_error, handle_rust_error_as, new_python_error, NetworkError};
+use crate::utils::network::parse_chain;
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct ElectrumConfig {
+    #[pyo3(get)]
+    pub chain: String,
+    #[pyo3(get)]
+    pub network: String,
+    #[pyo3(get)]
+    pub url: String,
+    #[pyo3(get)]
+    pub tls: bool,
+    #[pyo3(get)]
+    pub validate_domain: bool,
+    #[pyo3(get)]
+    pub timeout: u8,
+}
+
+#[pymethods]
+impl ElectrumConfig {
+    #[new]
+    #[pyo3(signature = (chain, network, url, tls=true, validate_domain=true, timeout=10))]
+    pub fn new(chain: String, network: String, url: String, tls: bool, validate_domain: bool, timeout: u8) -> PyResult<Self> {
+        parse_chain(chain.as_str(), network.as_str())?;
+
+        Ok(ElectrumConfig {
+            chain,
+            network,
+            url,
+            tls,
+            validate_domain,
+            timeout,
+        })
+    }
+
+    #[staticmethod]
+    pub fn default(chain: String, network: String) -> PyResult<Self> {
+        parse_chain(chain.as_str(), network.as_str())?;
+
+        let (url, tls) = match (chain.as_str(), network.as_str()) {
+            ("BTC", "main") => ("electrum.blockstream.info:50002", true),
+            ("BTC", "testnet") => ("electrum.blockstream.info:60002", true),
+            ("L-BTC", "main") => ("blockstream.info:995", true),
+            ("L-BTC", "testnet") => ("blockstream.info:465", true),
+            ("BTC", _) => ("127.0.0.1:19001", false),
+            _ => ("127.0.0.1:19002", false),
+        };
+
+        ElectrumConfig::new(chain, network, url.to_string(), tls, tls, 10)
+    }
+
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("chain", self.chain.clone())?;
+        dict.set_item("network", self.network.clone())?;
+        dict.set_item("url", self.url.clone())?;
+        dict.set_item("tls", self.tls)?;
+        dict.set_item("validate_domain", self.validate_domain)?;
+        dict.set_item("timeout", self.timeout)?;
+        Ok(dict)
+    }
+}
+
+fn confirmed_height(height: i64) -> Option<u32> {
+    if height > 0 {
+        Some(height as u32)
+    } else {
+        None
+    }
+}
+
+#[pyclass]
+pub struct ElectrumClient {
+    client: electrum_client::Client,
+    config: ElectrumConfig,
+}
+
+impl ChainBackend for ElectrumClient {
+    fn is_liquid(&self) -> bool {
+        self.config.chain == "L-BTC"
+    }
+
+    fn script_history(&self, script: &[u8]) -> PyResult<Vec<ScriptHistoryItem>> {
+        let history = handle_rust_error_as::<NetworkError, _, _>(
+            "could not fetch script history",
+            self.client.script_get_history(Script::from_bytes(script)),
+        )?;
+
+        Ok(history
+            .into_iter()
+            .map(|item| ScriptHistoryItem {
+                txid: item.tx_hash.to_string(),
+                height: confirmed_height(item.height as i64),
+            })
+            .collect())
+    }
+
+    fn utxos(&self, script: &[u8]) -> PyResult<Vec<Utxo>> {
+        if !self.is_liquid() {
+            let unspent = handle_rust_error_as::<NetworkError, _, _>(
+                "could not fetch unspent outputs",
+                self.client.script_list_unspent(Script::from_bytes(script)),
+            )?;
+
+            return Ok(unspent
+                .into_iter()
+                .map(|utxo| Utxo {
+                    txid: utxo.tx_hash.to_string(),
+                    vout: utxo.tx_pos as u32,
+                    value: Some(utxo.value),
+                    height: confirmed_height(utxo.height as i64),
+                })
+                .collect());
+        }
+
+        // Liquid servers cannot report values of confidential outputs, so the
+        // unspent outputs are derived from the transactions in the history
+        let mut transactions = Vec::new();
+        for item in self.script_history(script)? {
+            let tx: elements::Transaction = handle_rust_error(
+                "could not parse transaction",
+                deserialize(self.transaction(item.txid.as_str())?.as_slice()),
+            )?;
+            transactions.push((item, tx));
+        }
+
+        let spent: HashSet<(String, u32)> = transactions
+            .iter()
+            .flat_map(|(_, tx)| tx.input.iter())
+            .map(|input| (input.previous_output.txid.to_string(), input.previous_output.vout))
+            .collect();
+
+        Ok(transactions
+            .iter()
+            .flat_map(|(item, tx)| {
+                tx.output
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, out)| out.script_pubkey.as_bytes() == script)
+                    .map(|(vout, out)| Utxo {
+                        txid: item.txid.clone(),
+                        vout: vout as u32,
+                        value: out.value.explicit(),
+                        height: item.height,
+                    })
+            })
+            .filter(|utxo| !spent.contains(&(utxo.txid.clone(), utxo.vout)))
+            .collect())
+    }
+
+    fn transaction(&self, txid: &str) -> PyResult<Vec<u8>> {
+        let txid = handle_rust_error("could not parse transaction id", Txid::from_str(txid))?;
+        handle_rust_error_as::<NetworkError, _, _>("could not fetch transaction", self.client.transaction_get_raw(&txid))
+    }
+
+    fn broadcast(&self, tx: &[u8]) -> PyResult<String> {
+        handle_rust_error_as::<NetworkError, _, _>(
+            "could not broadcast transaction",
+            self.client.transaction_broadcast_raw(tx),
+        )
+        .map(|txid| txid.to_string())
+    }
+
+    fn tip_height(&self) -> PyResult<u32> {
+        handle_rust_error_as::<NetworkError, _, _>(
+            "could not fetch tip height",
+            self.client.block_headers_subscribe_raw(),
+        )
+        .map(|header| header.height as u32)
+    }
+
+    // Electrum reports BTC per kvB
+    fn estimate_fee(&self, blocks: usize) -> PyResult<f64> {
+        let fee = handle_rust_error_as::<NetworkError, _, _>("could not estimate fee", self.client.estimate_fee(blocks))?;
+        if fee < 0.0 {
+            return Err(new_python_error::<NetworkError>("electrum server has no fee estimate"));
+        }
+
+        Ok(fee * 100_000.0)
+    }
+}
+
+#[pymethods]
+impl ElectrumClient {
+    #[new]
+    pub fn new(py: Python, config: ElectrumConfig) -> PyResult<Self> {
+        let url = if config.url.contains("://") {
+            config.url.clone()
+        } else {
+            format!("{}://{}", if config.tls { "ssl" } else { "tcp" }, config.url)
+        };
+        let electrum_config = ConfigBuilder::new()
+            .timeout(Some(config.timeout))
+            .validate_domain(config.validate_domain)
+            .build();
+
+        let client = py.allow_threads(|| {
+            handle_rust_error_as::<NetworkError, _, _>(
+                "could not connect to electrum server",
+                electrum_client::Client::from_config(url.as_str(), electrum_config),
+            )
+        })?;
+
+        Ok(ElectrumClient { client, config })
+    }
+
+    #[getter]
+    pub fn config(&self) -> ElectrumConfig {
+        self.config.clone()
+    }
+
+    pub fn get_script_history(&self, py: Python, script: Vec<u8>) -> PyResult<Vec<ScriptHistoryItem>> {
+        py.allow_threads(|| self.script_history(script.as_slice()))
+    }
+
+    pub fn get_address_history(&self, py: Python, address: String) -> PyResult<Vec<ScriptHistoryItem>> {
+        let script = address_script_pubkey(self.config.chain.as_str(), address.as_str())?;
+        py.allow_threads(|| self.script_history(script.as_slice()))
+    }
+
+    pub fn get_utxos(&self, py: Python, script: Vec<u8>) -> PyResult<Vec<Utxo>> {
+        py.allow_threads(|| self.utxos(script.as_slice()))
+    }
+
+    pub fn get_transaction(&self, py: Python, txid: String) -> PyResult<String> {
+        py.allow_threads(|| self.transaction(txid.as_str()).map(hex::encode))
+    }
+
+    #[pyo3(name = "broadcast")]
+    pub fn broadcast_hex(&self, py: Python, tx_hex: String) -> PyResult<String> {
+        let tx = handle_rust_error("could not decode transaction hex", hex::decode(tx_hex))?;
+        py.allow_threads(|| self.broadcast(tx.as_slice()))
+    }
+
+    pub fn get_tip_height(&self, py: Python) -> PyResult<u32> {
+        py.allow_threads(|| self.tip_height())
+    }
+
+    // sat per vbyte
+    #[pyo3(name = "estimate_fee", signature = (blocks=2))]
+    pub fn estimate_fee_rate(&self, py: Python, blocks: usize) -> PyResult<f64> {
+        py.allow_threads(|| self.estimate_fee(blocks))
+    }
+}
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+use bitcoin::hashes::{sha256, Hash};
+use pyo3::{pyclass, pymethods, PyResult, Python};
+use serde::Deserialize;
+
+use crate::client::backend::ChainBackend;
+use crate::client::config::ClientConfig;
+use crate::client::http::HttpTransport;
+use crate::types::onchain::{ScriptHistoryItem, Utxo};
+use crate::utils::address::address_script_pubkey;
+use crate::utils::errors::{handle_rust_error, new_python_error, NetworkError};
+use crate::utils::network::parse_chain;
+
+// Esplora returns confirmed transactions of a script in pages of this size
+const CHAIN_PAGE_SIZE: usize = 25;
+
+#[derive(Deserialize)]
+struct TxStatus {
+    confirmed: bool,
+    block_height: Option<u32>,
+}
+
+impl TxStatus {
+    fn height(&self) -> Option<u32> {
+        if self.confirmed {
+            self.block_height
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct EsploraTx {
+    txid: String,
+    status: TxStatus,
+}
+
+#[derive(Deserialize)]
+struct EsploraUtxo {
+    txid: String,
+    vout: u32,
+    // missing for confidential Liquid outputs
+    value: Option<u64>,
+    status: TxStatus,
+}
+
+fn script_hash(script: &[u8]) -> String {
+    let mut hash = sha256::Hash::hash(script).to_byte_array();
+    hash.reverse();
+    hex::encode(hash)
+}
+
+#[pyclass]
+pub struct EsploraClient {
+    http: HttpTransport,
+    chain: String,
+    network: String,
+}
+
+impl ChainBackend for EsploraClient {
+    fn is_liquid(&self) -> bool {
+        self.chain == "L-BTC"
+    }
+
+    fn script_history(&self, script: &[u8]) -> PyResult<Vec<ScriptHistoryItem>> {
+        let script_hash = script_hash(script);
+        let mut txs: Vec<EsploraTx> = self.http.get("could not fetch script history", &format!("/scripthash/{}/txs", script_hash))?;
+
+        let mut confirmed = txs.iter().filter(|tx| tx.status.confirmed).count();
+        while confirmed == CHAIN_PAGE_SIZE {
+            let last_seen = txs.last().map(|tx| tx.txid.clone()).unwrap_or_default();
+            let page: Vec<EsploraTx> = self.http.get(
+                "could not fetch script history",
+                &format!("/scripthash/{}/txs/chain/{}", script_hash, last_seen),
+            )?;
+            confirmed = page.len();
+            txs.extend(page);
+        }
+
+        Ok(txs
+            .into_iter()
+            .map(|tx| ScriptHistoryItem {
+                height: tx.status.height(),
+                txid: tx.txid,
+            })
+            .collect())
+    }
+
+    fn utxos(&self, script: &[u8]) -> PyResult<Vec<Utxo>> {
+        let utxos: Vec<EsploraUtxo> =
+            self.http.get("could not fetch unspent outputs", &format!("/scripthash/{}/utxo", script_hash(script)))?;
+
+        Ok(utxos
+            .into_iter()
+            .map(|utxo| Utxo {
+                height: utxo.status.height(),
+                txid: utxo.txid,
+                vout: utxo.vout,
+                value: utxo.value,
+            })
+            .collect())
+    }
+
+    fn transaction(&self, txid: &str) -> PyResult<Vec<u8>> {
+        let tx_hex = self.http.get_text("could not fetch transaction", &format!("/tx/{}/hex", txid))?;
+        handle_rust_error("could not decode transaction hex", hex::decode(tx_hex.trim()))
+    }
+
+    fn broadcast(&self, tx: &[u8]) -> PyResult<String> {
+        self.http
+            .post_text("could not broadcast transaction", "/tx", &hex::encode(tx))
+            .map(|txid| txid.trim().to_string())
+    }
+
+    fn tip_height(&self) -> PyResult<u32> {
+        let height = self.http.get_text("could not fetch tip height", "/blocks/tip/height")?;
+        handle_rust_error("could not parse tip height", height.trim().parse::<u32>())
+    }
+
+    // Esplora reports sat/vbyte for a fixed set of confirmation targets, the
+    // closest target that is not slower than requested is used
+    fn estimate_fee(&self, blocks: usize) -> PyResult<f64> {
+        let estimates: BTreeMap<String, f64> = self.http.get("could not estimate fee", "/fee-estimates")?;
+        let estimates: BTreeMap<usize, f64> = estimates
+            .into_iter()
+            .filter_map(|(target, fee)| target.parse::<usize>().ok().map(|target| (target, fee)))
+            .collect();
+
+        estimates
+            .range(..=blocks)
+            .next_back()
+            .or_else(|| estimates.iter().next())
+            .map(|(_, fee)| *fee)
+            .ok_or_else(|| new_python_error::<NetworkError>("esplora server has no fee estimate"))
+    }
+}
+
+#[pymethods]
+impl EsploraClient {
+    #[new]
+    #[pyo3(signature = (chain, network, url, config=None))]
+    pub fn new(chain: String, network: String, url: String, config: Option<ClientConfig>) -> PyResult<Self> {
+        parse_chain(chain.as_str(), network.as_str())?;
+
+        Ok(EsploraClient {
+            http: HttpTransport::new(url, &config.unwrap_or_default())?,
+            chain,
+            network,
+        })
+    }
+
+    #[getter]
+    pub fn chain(&self) -> String {
+        self.chain.clone()
+    }
+
+    #[getter]
+    pub fn network(&self) -> String {
+        self.network.clone()
+    }
+
+    #[getter]
+    pub fn url(&self) -> String {
+        self.http.base_url().to_string()
+    }
+
+    pub fn get_script_history(&self, py: Python, script: Vec<u8>) -> PyResult<Vec<ScriptHistoryItem>> {
+        py.allow_threads(|| self.script_history(script.as_slice()))
+    }
+
+    pub fn get_address_history(&self, py: Python, address: String) -> PyResult<Vec<ScriptHistoryItem>> {
+        let script = address_script_pubkey(self.chain.as_str(), address.as_str())?;
+        py.allow_threads(|| self.script_history(script.as_slice()))
+    }
+
+    pub fn get_utxos(&self, py: Python, script: Vec<u8>) -> PyResult<Vec<Utxo>> {
+        py.allow_threads(|| self.utxos(script.as_slice()))
+    }
+
+    pub fn get_transaction(&self, py: Python, txid: String) -> PyResult<String> {
+        py.allow_threads(|| self.transaction(txid.as_str()).map(hex::encode))
+    }
+
+    #[pyo3(name = "broadcast")]
+    pub fn broadcast_hex(&self, py: Python, tx_hex: String) -> PyResult<String> {
+        let tx = handle_rust_error("could not decode transaction hex", hex::decode(tx_hex))?;
+        py.allow_threads(|| self.broadcast(tx.as_slice()))
+    }
+
+    pub fn get_tip_height(&self, py: Python) -> PyResult<u32> {
+        py.allow_threads(|| self.tip_height())
+    }
+
+    // sat per vbyte
+    #[pyo3(name = "estimate_fee", signature = (blocks=2))]
+    pub fn estimate_fee_rate(&self, py: Python, blocks: usize) -> PyResult<f64> {
+        py.allow_threads(|| self.estimate_fee(blocks))
+    }
+}
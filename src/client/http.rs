@@ -0,0 +1,138 @@
+use std::sync::{Arc, RwLock};
+use std::thread::sleep;
+use std::time::Duration;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::PyResult;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::client::config::ClientConfig;
+use crate::utils::errors::{handle_http_error, handle_rust_error, handle_rust_error_as, NetworkError};
+
+// Upper bound of the delay between two retries, however many are configured
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(60);
+
+pub(crate) struct HttpTransport {
+    agent: ureq::Agent,
+    base_url: String,
+    headers: RwLock<Vec<(String, String)>>,
+    retries: u32,
+    retry_backoff: Duration,
+}
+
+fn tls_config(root_certs: &[String]) -> PyResult<Arc<rustls::ClientConfig>> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    for pem in root_certs {
+        for cert in rustls_pemfile::certs(&mut pem.as_bytes()) {
+            let cert = handle_rust_error("could not parse root certificate", cert)?;
+            handle_rust_error("could not add root certificate", roots.add(cert))?;
+        }
+    }
+
+    Ok(Arc::new(
+        rustls::ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    ))
+}
+
+fn duration(name: &str, secs: f64) -> PyResult<Duration> {
+    handle_rust_error_as::<PyValueError, _, _>(&format!("invalid {}", name), Duration::try_from_secs_f64(secs))
+}
+
+fn is_retryable(err: &ureq::Error) -> bool {
+    match err {
+        ureq::Error::Status(status, _) => *status >= 500,
+        ureq::Error::Transport(_) => true,
+    }
+}
+
+impl HttpTransport {
+    pub fn new(base_url: String, config: &ClientConfig) -> PyResult<Self> {
+        let mut builder = ureq::AgentBuilder::new();
+        if let Some(timeout) = config.connect_timeout {
+            builder = builder.timeout_connect(duration("connect_timeout", timeout)?);
+        }
+        if let Some(timeout) = config.read_timeout {
+            builder = builder.timeout_read(duration("read_timeout", timeout)?);
+        }
+        if let Some(user_agent) = &config.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+        if let Some(proxy) = &config.proxy {
+            let proxy = handle_rust_error_as::<NetworkError, _, _>("could not parse proxy", ureq::Proxy::new(proxy))?;
+            builder = builder.proxy(proxy);
+        }
+        if !config.root_certs.is_empty() {
+            builder = builder.tls_config(tls_config(&config.root_certs)?);
+        }
+
+        Ok(HttpTransport {
+            agent: builder.build(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            headers: RwLock::new(config.headers.clone().into_iter().collect()),
+            retries: config.retries,
+            retry_backoff: duration("retry_backoff", config.retry_backoff)?,
+        })
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    // Replaces the header for all following requests, used when credentials
+    // change while the transport is in use
+    pub fn set_header(&self, name: &str, value: String) {
+        let mut headers = self.headers.write().expect("headers lock poisoned");
+        headers.retain(|(header, _)| !header.eq_ignore_ascii_case(name));
+        headers.push((name.to_string(), value));
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        let mut req = self.agent.request(method, &format!("{}{}", self.base_url, path));
+        for (name, value) in self.headers.read().expect("headers lock poisoned").iter() {
+            req = req.set(name, value);
+        }
+        req
+    }
+
+    // GET requests are idempotent and retried with exponential backoff on
+    // transport errors and 5xx responses
+    fn get_response(&self, msg: &str, path: &str) -> PyResult<ureq::Response> {
+        let mut attempt = 0;
+        let res = loop {
+            match self.request("GET", path).call() {
+                Err(err) if attempt < self.retries && is_retryable(&err) => {
+                    let factor = 2u32.checked_pow(attempt).unwrap_or(u32::MAX);
+                    sleep(self.retry_backoff.saturating_mul(factor).min(MAX_RETRY_BACKOFF));
+                    attempt += 1;
+                }
+                res => break res,
+            }
+        };
+
+        handle_http_error(msg, res)
+    }
+
+    pub fn get<T: DeserializeOwned>(&self, msg: &str, path: &str) -> PyResult<T> {
+        let res = self.get_response(msg, path)?;
+        handle_rust_error(msg, res.into_json())
+    }
+
+    pub fn get_text(&self, msg: &str, path: &str) -> PyResult<String> {
+        let res = self.get_response(msg, path)?;
+        handle_rust_error(msg, res.into_string())
+    }
+
+    pub fn post<B: Serialize, T: DeserializeOwned>(&self, msg: &str, path: &str, body: &B) -> PyResult<T> {
+        let res = handle_http_error(msg, self.request("POST", path).send_json(body))?;
+        handle_rust_error(msg, res.into_json())
+    }
+
+    pub fn post_text(&self, msg: &str, path: &str, body: &str) -> PyResult<String> {
+        let res = handle_http_error(msg, self.request("POST", path).send_string(body))?;
+        handle_rust_error(msg, res.into_string())
+    }
+}
@@ -1 +1,9 @@
+pub mod async_client;
+pub mod backend;
+pub mod bitcoind;
 pub mod boltz;
+pub mod config;
+pub mod electrum;
+pub mod esplora;
+pub mod http;
+pub mod ws;
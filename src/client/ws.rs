@@ -0,0 +1,231 @@
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, TryLockError};
+use std::time::Duration;
+
+use pyo3::exceptions::PyStopAsyncIteration;
+use pyo3::types::{PyAnyMethods, PyCFunction, PyDict, PyTuple};
+use pyo3::{pyclass, pymethods, Bound, PyAny, PyRef, PyResult, Python};
+use serde::Deserialize;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+use crate::types::status::{SwapUpdate, SwapUpdateRaw};
+use crate::utils::errors::{api_error, handle_rust_error, handle_rust_error_as, NetworkError};
+
+#[derive(Deserialize)]
+struct WsMessage {
+    event: String,
+    #[serde(default)]
+    args: Vec<serde_json::Value>,
+}
+
+fn tcp_stream(socket: &WebSocket<MaybeTlsStream<TcpStream>>) -> Option<&TcpStream> {
+    match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => Some(stream),
+        MaybeTlsStream::Rustls(stream) => Some(stream.get_ref()),
+        _ => None,
+    }
+}
+
+struct Inner {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    pending: VecDeque<SwapUpdate>,
+    closed: Arc<AtomicBool>,
+}
+
+impl Inner {
+    fn next_update(&mut self) -> PyResult<Option<SwapUpdate>> {
+        loop {
+            if let Some(update) = self.pending.pop_front() {
+                return Ok(Some(update));
+            }
+            if self.closed.load(Ordering::SeqCst) {
+                return Ok(None);
+            }
+
+            let msg = match self.socket.read() {
+                Ok(msg) => msg,
+                Err(tungstenite::Error::ConnectionClosed) | Err(tungstenite::Error::AlreadyClosed) => {
+                    self.closed.store(true, Ordering::SeqCst);
+                    continue;
+                }
+                // The socket was shut down by close while we were reading
+                Err(_) if self.closed.load(Ordering::SeqCst) => continue,
+                // Only happens when a read timeout is set
+                Err(tungstenite::Error::Io(err)) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Ok(None)
+                }
+                Err(err) => return handle_rust_error_as::<NetworkError, _, _>("could not read from websocket", Err(err)),
+            };
+
+            match msg {
+                Message::Text(text) => self.handle_text(text.as_str())?,
+                Message::Close(_) => self.closed.store(true, Ordering::SeqCst),
+                _ => {}
+            }
+        }
+    }
+
+    fn subscribe(&mut self, swap_ids: Vec<String>) -> PyResult<()> {
+        let subscribe = serde_json::json!({
+            "op": "subscribe",
+            "channel": "swap.update",
+            "args": swap_ids,
+        });
+        handle_rust_error_as::<NetworkError, _, _>(
+            "could not subscribe to swap updates",
+            self.socket.send(Message::Text(subscribe.to_string())),
+        )
+    }
+
+    fn handle_text(&mut self, text: &str) -> PyResult<()> {
+        let msg: WsMessage = handle_rust_error("could not parse websocket message", serde_json::from_str(text))?;
+        match msg.event.as_str() {
+            // An update that cannot be parsed is skipped rather than failing the
+            // whole stream, the others in the message are still delivered
+            "update" => {
+                self.pending.extend(
+                    msg.args
+                        .into_iter()
+                        .filter_map(|arg| serde_json::from_value::<SwapUpdateRaw>(arg).ok())
+                        .map(SwapUpdate::from),
+                );
+                Ok(())
+            }
+            "error" => Err(api_error("websocket error", None, Some(text.to_string()))),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Clone)]
+pub struct SwapStatusStream {
+    inner: Arc<Mutex<Inner>>,
+    // A blocked read holds the lock of inner, so close shuts down this clone
+    // of the socket instead
+    tcp: Option<Arc<TcpStream>>,
+    closed: Arc<AtomicBool>,
+}
+
+#[pymethods]
+impl SwapStatusStream {
+    #[new]
+    pub fn new(ws_url: String, swap_ids: Vec<String>) -> PyResult<Self> {
+        let (socket, _) = handle_rust_error_as::<NetworkError, _, _>("could not connect to websocket", tungstenite::connect(ws_url))?;
+        let tcp = match tcp_stream(&socket) {
+            Some(stream) => Some(Arc::new(handle_rust_error_as::<NetworkError, _, _>(
+                "could not connect to websocket",
+                stream.try_clone(),
+            )?)),
+            None => None,
+        };
+        let closed = Arc::new(AtomicBool::new(false));
+        let mut inner = Inner {
+            socket,
+            pending: VecDeque::new(),
+            closed: closed.clone(),
+        };
+        inner.subscribe(swap_ids)?;
+
+        Ok(SwapStatusStream {
+            inner: Arc::new(Mutex::new(inner)),
+            tcp,
+            closed,
+        })
+    }
+
+    pub fn next_update(&self, py: Python) -> PyResult<Option<SwapUpdate>> {
+        let inner = self.inner.clone();
+        py.allow_threads(move || inner.lock().expect("websocket lock poisoned").next_update())
+    }
+
+    // The closing handshake is only done when no read is in progress, the
+    // socket is shut down either way so that a blocked read returns
+    pub fn close(&self, py: Python) -> PyResult<()> {
+        self.closed.store(true, Ordering::SeqCst);
+        py.allow_threads(|| {
+            let res = match self.inner.try_lock() {
+                Ok(mut inner) => match inner.socket.close(None) {
+                    Err(tungstenite::Error::ConnectionClosed) | Err(tungstenite::Error::AlreadyClosed) => Ok(()),
+                    res => res,
+                },
+                Err(TryLockError::WouldBlock) => Ok(()),
+                Err(TryLockError::Poisoned(_)) => panic!("websocket lock poisoned"),
+            };
+            if let Some(tcp) = &self.tcp {
+                let _ = tcp.shutdown(Shutdown::Both);
+            }
+            handle_rust_error_as::<NetworkError, _, _>("could not close websocket", res)
+        })
+    }
+
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python) -> PyResult<Option<SwapUpdate>> {
+        self.next_update(py)
+    }
+
+    fn __aiter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    // The blocking read runs in the default executor of the event loop
+    fn __anext__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let event_loop = py.import_bound("asyncio")?.call_method0("get_running_loop")?;
+        let stream = self.clone();
+        let next = PyCFunction::new_closure_bound(
+            py,
+            None,
+            None,
+            move |args: &Bound<'_, PyTuple>, _kwargs: Option<&Bound<'_, PyDict>>| -> PyResult<SwapUpdate> {
+                match stream.next_update(args.py())? {
+                    Some(update) => Ok(update),
+                    None => Err(PyStopAsyncIteration::new_err("websocket closed")),
+                }
+            },
+        )?;
+        event_loop.call_method1("run_in_executor", (py.None(), next))
+    }
+}
+
+impl SwapStatusStream {
+    // With a read timeout, next_update returns None when no update arrived in
+    // time; is_closed tells that apart from a closed websocket
+    pub(crate) fn set_read_timeout(&self, timeout: Option<Duration>) -> PyResult<()> {
+        let res = match &self.tcp {
+            Some(tcp) => tcp.set_read_timeout(timeout),
+            None => Ok(()),
+        };
+        handle_rust_error_as::<NetworkError, _, _>("could not set websocket timeout", res)
+    }
+
+    pub(crate) fn poll_update(&self) -> PyResult<Option<SwapUpdate>> {
+        self.inner.lock().expect("websocket lock poisoned").next_update()
+    }
+
+    pub(crate) fn subscribe(&self, swap_ids: Vec<String>) -> PyResult<()> {
+        self.inner.lock().expect("websocket lock poisoned").subscribe(swap_ids)
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+}
+
+pub fn ws_url(base_url: &str) -> String {
+    let url = if let Some(rest) = base_url.strip_prefix("https://") {
+        format!("wss://{}", rest)
+    } else if let Some(rest) = base_url.strip_prefix("http://") {
+        format!("ws://{}", rest)
+    } else {
+        base_url.to_string()
+    };
+
+    format!("{}/ws", url.trim_end_matches('/'))
+}
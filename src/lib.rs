@@ -1,9 +1,11 @@
 use pyo3::prelude::PyModule;
 use pyo3::{pymodule, wrap_pyfunction, Bound, PyResult};
 
-use swaps::bitcoin;
+use swaps::{bitcoin, liquid, manager, refund, rescue, state};
 
 mod client;
+mod mock;
+mod store;
 mod swaps;
 mod types;
 mod utils;
@@ -11,16 +13,59 @@ mod utils;
 #[pymodule]
 fn boltz_client_bindings(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<bitcoin::BtcSwapScript>()?;
+    m.add_class::<bitcoin::BtcSwapTx>()?;
+    m.add_class::<liquid::LBtcSwapScript>()?;
+    m.add_class::<liquid::LBtcSwapTx>()?;
+    m.add_class::<state::SwapStateMachine>()?;
+    m.add_class::<rescue::RescueSwap>()?;
+    m.add_class::<rescue::RescueFile>()?;
     m.add_class::<types::submarine::CreateSubmarineResponse>()?;
     m.add_class::<types::submarine::SwapTree>()?;
     m.add_class::<types::submarine::Leaf>()?;
+    m.add_class::<types::submarine::SubmarineClaimDetails>()?;
+    m.add_class::<types::reverse::CreateReverseResponse>()?;
+    m.add_class::<types::chain::CreateChainResponse>()?;
+    m.add_class::<types::chain::ChainSwapDetails>()?;
     m.add_class::<client::boltz::Client>()?;
+    m.add_class::<client::async_client::AsyncClient>()?;
+    m.add_class::<client::config::ClientConfig>()?;
+    m.add_class::<client::electrum::ElectrumConfig>()?;
+    m.add_class::<client::electrum::ElectrumClient>()?;
+    m.add_class::<client::esplora::EsploraClient>()?;
+    m.add_class::<client::bitcoind::BitcoindRpcBackend>()?;
+    m.add_class::<client::ws::SwapStatusStream>()?;
+    m.add_class::<mock::server::MockBoltzServer>()?;
+    m.add_class::<types::status::SwapStatus>()?;
+    m.add_class::<types::status::SwapUpdate>()?;
     m.add_class::<types::client::GetSubmarinePairsResponse>()?;
     m.add_class::<types::client::HeightResponse>()?;
     m.add_class::<types::client::SubmarineFees>()?;
     m.add_class::<types::client::PairLimits>()?;
     m.add_class::<types::client::SubmarinePair>()?;
+    m.add_class::<types::client::GetReversePairsResponse>()?;
+    m.add_class::<types::client::ReversePair>()?;
+    m.add_class::<types::client::ReverseLimits>()?;
+    m.add_class::<types::client::ReverseFees>()?;
+    m.add_class::<types::client::PairMinerFees>()?;
+    m.add_class::<types::client::GetChainPairsResponse>()?;
+    m.add_class::<types::client::ChainPair>()?;
+    m.add_class::<types::client::ChainFees>()?;
+    m.add_class::<types::client::ChainMinerFees>()?;
+    m.add_class::<types::onchain::Utxo>()?;
+    m.add_class::<types::onchain::ScriptHistoryItem>()?;
+    m.add_class::<types::transaction::SwapTransaction>()?;
+    m.add_class::<types::transaction::SubmarineTransaction>()?;
+    m.add_class::<utils::preimage::Preimage>()?;
+    m.add_class::<utils::derivation::SwapKeyDeriver>()?;
+    m.add_class::<store::swap_store::SwapStore>()?;
+    m.add_class::<store::swap_store::StoredSwap>()?;
+    m.add_class::<store::swap_store::SwapStatusEntry>()?;
+    m.add_class::<manager::SwapManager>()?;
+    m.add_class::<manager::SwapEvent>()?;
+    m.add_class::<refund::RefundScanner>()?;
+    m.add_class::<refund::RefundResult>()?;
     m.add_function(wrap_pyfunction!(utils::keys::new_keys, m)?)?;
     m.add_function(wrap_pyfunction!(utils::address::validate_address, m)?)?;
+    utils::errors::register_errors(m)?;
     Ok(())
 }
@@ -0,0 +1,196 @@
+use bitcoin::absolute::LockTime;
+use bitcoin::consensus::encode::deserialize;
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::secp256k1::{Keypair, Secp256k1};
+use bitcoin::sighash::{Prevouts, SighashCache};
+use bitcoin::transaction::Version;
+use bitcoin::{Amount, OutPoint, ScriptBuf, Sequence, TapSighashType, Transaction, TxIn, TxOut, Witness};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::mock::swaps::{Lockup, MockResult};
+use crate::swaps::musig::{partial_sign, KeyPathSigner};
+
+const CLAIM_FEE: u64 = 1_000;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PartialSignature {
+    pub_nonce: String,
+    partial_signature: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ToSign {
+    index: usize,
+    transaction: String,
+    pub_nonce: String,
+}
+
+// The reverse claim request has the fields of ToSign at the top level, the
+// one of chain swaps nests them
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReverseClaimRequest {
+    preimage: String,
+    #[serde(flatten)]
+    to_sign: ToSign,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChainClaimRequest {
+    preimage: String,
+    to_sign: ToSign,
+}
+
+fn parse_request<T: DeserializeOwned>(body: &str) -> MockResult<T> {
+    serde_json::from_str(body).map_err(|err| format!("invalid request: {}", err))
+}
+
+fn funded_output(lockup: &Lockup) -> MockResult<(OutPoint, TxOut)> {
+    let (_, tx_hex) = lockup
+        .transaction
+        .as_ref()
+        .ok_or_else(|| "lockup transaction not found".to_string())?;
+    let tx_bytes = hex::decode(tx_hex).map_err(|err| format!("invalid lockup transaction: {}", err))?;
+    let tx: Transaction = deserialize(&tx_bytes).map_err(|err| format!("invalid lockup transaction: {}", err))?;
+    let script = lockup_script(lockup)?;
+    let script_pubkey = ScriptBuf::new_p2tr_tweaked(
+        script
+            .taproot_spendinfo()
+            .map_err(|err| format!("invalid swap tree: {:?}", err))?
+            .output_key(),
+    );
+
+    tx.output
+        .iter()
+        .enumerate()
+        .find(|(_, out)| out.script_pubkey == script_pubkey)
+        .map(|(vout, out)| (OutPoint::new(tx.compute_txid(), vout as u32), out.clone()))
+        .ok_or_else(|| "lockup transaction does not pay to swap script".to_string())
+}
+
+fn lockup_script(lockup: &Lockup) -> MockResult<&boltz_client::BtcSwapScript> {
+    lockup
+        .script
+        .as_ref()
+        .ok_or_else(|| "the mock server can only sign BTC swaps".to_string())
+}
+
+fn key_path_signer(server_keys: &Keypair, lockup: &Lockup, sighash: [u8; 32]) -> MockResult<KeyPathSigner> {
+    let script = lockup_script(lockup)?;
+    let spend_info = script
+        .taproot_spendinfo()
+        .map_err(|err| format!("invalid swap tree: {:?}", err))?;
+
+    KeyPathSigner::new(
+        script.musig_keyagg_cache(),
+        spend_info.tap_tweak().as_byte_array(),
+        server_keys,
+        sighash,
+    )
+    .map_err(|err| err.to_string())
+}
+
+/// Claim of the lockup of a submarine swap by the server: the details of the
+/// response are signed by the user and the partial signature is checked with
+/// the returned signer.
+pub(crate) fn submarine_claim(server_keys: &Keypair, lockup: &Lockup, preimage: &[u8]) -> MockResult<(KeyPathSigner, Value)> {
+    let (outpoint, funded) = funded_output(lockup)?;
+    let (server_key, _) = server_keys.x_only_public_key();
+    let tx = Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: outpoint,
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: funded.value.checked_sub(Amount::from_sat(CLAIM_FEE)).unwrap_or(Amount::ZERO),
+            script_pubkey: ScriptBuf::new_p2tr(&Secp256k1::new(), server_key, None),
+        }],
+    };
+
+    let sighash = SighashCache::new(&tx)
+        .taproot_key_spend_signature_hash(0, &Prevouts::All(&[&funded]), TapSighashType::Default)
+        .map_err(|err| format!("could not compute signature hash: {}", err))?
+        .to_byte_array();
+    let signer = key_path_signer(server_keys, lockup, sighash)?;
+    let details = json!({
+        "preimage": hex::encode(preimage),
+        "pubNonce": signer.pub_nonce(),
+        "publicKey": server_keys.public_key().to_string(),
+        "transactionHash": hex::encode(sighash),
+    });
+
+    Ok((signer, details))
+}
+
+pub(crate) fn verify_submarine_claim(server_keys: &Keypair, signer: KeyPathSigner, body: &str) -> MockResult<()> {
+    let signature: PartialSignature = parse_request(body)?;
+    signer
+        .sign(server_keys, &signature.pub_nonce, &signature.partial_signature)
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+pub(crate) fn refund_request(body: &str) -> MockResult<ToSign> {
+    parse_request(body)
+}
+
+/// Parses the claim request of a reverse or chain swap and checks the
+/// preimage against the preimage hash of the swap.
+pub(crate) fn claim_request(chain: bool, preimage_hash: &sha256::Hash, body: &str) -> MockResult<ToSign> {
+    let (preimage, to_sign) = if chain {
+        let req: ChainClaimRequest = parse_request(body)?;
+        (req.preimage, req.to_sign)
+    } else {
+        let req: ReverseClaimRequest = parse_request(body)?;
+        (req.preimage, req.to_sign)
+    };
+    let preimage = hex::decode(preimage).map_err(|err| format!("invalid preimage: {}", err))?;
+    if sha256::Hash::hash(&preimage) != *preimage_hash {
+        return Err("invalid preimage".to_string());
+    }
+
+    Ok(to_sign)
+}
+
+/// Partial signature of the server for a key path spend of lockup by the user.
+pub(crate) fn partial_signature(server_keys: &Keypair, lockup: &Lockup, to_sign: &ToSign) -> MockResult<Value> {
+    let (outpoint, funded) = funded_output(lockup)?;
+    let tx_bytes = hex::decode(&to_sign.transaction).map_err(|err| format!("invalid transaction: {}", err))?;
+    let tx: Transaction = deserialize(&tx_bytes).map_err(|err| format!("invalid transaction: {}", err))?;
+    if tx.input.len() != 1 || to_sign.index != 0 {
+        return Err("only transactions with a single input can be signed".to_string());
+    }
+    if tx.input[0].previous_output != outpoint {
+        return Err("transaction does not spend the lockup output".to_string());
+    }
+
+    let sighash = SighashCache::new(&tx)
+        .taproot_key_spend_signature_hash(0, &Prevouts::All(&[&funded]), TapSighashType::Default)
+        .map_err(|err| format!("could not compute signature hash: {}", err))?;
+    let script = lockup_script(lockup)?;
+    let spend_info = script
+        .taproot_spendinfo()
+        .map_err(|err| format!("invalid swap tree: {:?}", err))?;
+    let (partial_sig, pub_nonce) = partial_sign(
+        script.musig_keyagg_cache(),
+        spend_info.tap_tweak().as_byte_array(),
+        server_keys,
+        &to_sign.pub_nonce,
+        &hex::encode(sighash.to_byte_array()),
+    )
+    .map_err(|err| err.to_string())?;
+
+    Ok(json!({
+        "pubNonce": hex::encode(pub_nonce.serialize()),
+        "partialSignature": hex::encode(partial_sig.serialize()),
+    }))
+}
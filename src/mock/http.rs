@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::thread::sleep;
+use std::time::Duration;
+
+pub(crate) struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+// Peeks at the request head without consuming it, so the stream can still be
+// handed to tungstenite for the WebSocket handshake
+pub(crate) fn is_websocket_upgrade(stream: &TcpStream) -> io::Result<bool> {
+    let mut buf = [0u8; 4096];
+    loop {
+        let read = stream.peek(&mut buf)?;
+        if read == 0 {
+            return Ok(false);
+        }
+
+        let head = String::from_utf8_lossy(&buf[..read]).to_lowercase();
+        if head.contains("\r\n\r\n") || read == buf.len() {
+            return Ok(head.contains("upgrade: websocket"));
+        }
+        sleep(Duration::from_millis(1));
+    }
+}
+
+pub(crate) fn read_request(stream: &TcpStream) -> io::Result<Option<Request>> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line)? == 0 {
+        return Ok(None);
+    }
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => (method.to_uppercase(), target),
+        _ => return Ok(None),
+    };
+    let path = target.split('?').next().unwrap_or_default().to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+        .unwrap_or_default();
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Some(Request {
+        method,
+        path,
+        body: String::from_utf8_lossy(&body).to_string(),
+    }))
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
+
+pub(crate) fn write_response(mut stream: &TcpStream, status: u16, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason(status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
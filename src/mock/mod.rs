@@ -0,0 +1,4 @@
+mod cooperative;
+mod http;
+pub mod server;
+mod swaps;
@@ -0,0 +1,597 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{sleep, spawn, JoinHandle};
+use std::time::Duration;
+
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::key::rand::thread_rng;
+use bitcoin::secp256k1::{Keypair, Secp256k1};
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, PyObject, PyRefMut, PyResult, Python};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tungstenite::Message;
+
+use crate::client::ws::ws_url;
+use crate::mock::cooperative::{
+    claim_request, partial_signature, refund_request, submarine_claim, verify_submarine_claim,
+};
+use crate::mock::http::{is_websocket_upgrade, read_request, write_response, Request};
+use crate::mock::swaps::{
+    chain_pairs, create_chain, create_invoice, create_reverse, create_submarine, funding_transaction, restore_swaps,
+    reverse_pairs, submarine_pairs, transaction_id, Lockup, MockResult, MockSwap, CURRENCIES,
+};
+use crate::swaps::musig::KeyPathSigner;
+use crate::types::status::SwapStatus;
+use crate::utils::errors::{
+    handle_rust_error, handle_rust_error_as, new_python_error, BoltzError, InvalidInvoiceError, NetworkError,
+};
+use crate::utils::network::parse_chain;
+
+const API_PREFIX: &str = "/v2";
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+struct Subscriber {
+    swap_ids: Arc<Mutex<HashSet<String>>>,
+    sender: Sender<String>,
+}
+
+struct State {
+    network: String,
+    server_keys: Keypair,
+    heights: HashMap<String, u32>,
+    swaps: HashMap<String, MockSwap>,
+    // sessions of cooperative submarine claims waiting for the partial
+    // signature of the user
+    claim_signers: HashMap<String, KeyPathSigner>,
+    transactions: HashMap<String, String>,
+    responses: HashMap<(String, String), (u16, String)>,
+    requests: Vec<(String, String, String)>,
+    subscribers: Vec<Subscriber>,
+}
+
+impl State {
+    fn height(&self, currency: &str) -> u32 {
+        self.heights.get(currency).copied().unwrap_or_default()
+    }
+
+    fn route(&mut self, req: &Request) -> (u16, Value) {
+        let path = match req.path.strip_prefix(API_PREFIX) {
+            Some(path) => path,
+            None => return not_found(),
+        };
+
+        if let Some((status, body)) = self.responses.get(&(req.method.clone(), path.to_string())) {
+            return (*status, serde_json::from_str(body).unwrap_or_else(|_| json!(body)));
+        }
+
+        let created = match (req.method.as_str(), path) {
+            ("GET", "/swap/submarine") => return (200, submarine_pairs()),
+            ("GET", "/swap/reverse") => return (200, reverse_pairs()),
+            ("GET", "/swap/chain") => return (200, chain_pairs()),
+            ("GET", "/chain/heights") => return (200, json!(self.heights)),
+            ("POST", "/swap/submarine") => {
+                create_submarine(&self.network, &self.server_keys, |currency| self.height(currency), &req.body)
+            }
+            ("POST", "/swap/reverse") => {
+                create_reverse(&self.network, &self.server_keys, |currency| self.height(currency), &req.body)
+            }
+            ("POST", "/swap/chain") => {
+                create_chain(&self.network, &self.server_keys, |currency| self.height(currency), &req.body)
+            }
+            ("POST", "/swap/restore") => {
+                return match restore_swaps(&self.swaps, &req.body) {
+                    Ok(restored) => (200, restored),
+                    Err(err) => (400, json!({ "error": err })),
+                }
+            }
+            (method, _) if path.starts_with("/chain/") => return self.route_transaction(method, path, &req.body),
+            (method, _) if path.ends_with("/claim") || path.ends_with("/refund") => {
+                return self.route_cooperative(method, path, &req.body)
+            }
+            ("GET", _) if path.starts_with("/swap/") && path.matches('/').count() == 4 => return self.route_lockup(path),
+            ("GET", _) => {
+                return match path.strip_prefix("/swap/").and_then(|id| self.swaps.get(id)) {
+                    Some(swap) => (200, swap.to_json()),
+                    None => not_found(),
+                }
+            }
+            _ => return not_found(),
+        };
+
+        match created {
+            Ok((swap, response)) => {
+                let id = response["id"].as_str().unwrap_or_default().to_string();
+                self.swaps.insert(id, swap);
+                (201, response)
+            }
+            Err(err) => (400, json!({ "error": err })),
+        }
+    }
+
+    fn route_transaction(&mut self, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let parts: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+        match (method, parts.as_slice()) {
+            ("POST", ["chain", currency, "transaction"]) => {
+                let tx_hex = serde_json::from_str::<Value>(body)
+                    .ok()
+                    .and_then(|body| body["hex"].as_str().map(str::to_string))
+                    .unwrap_or_default();
+                match transaction_id(currency, &tx_hex) {
+                    Ok(id) => {
+                        self.transactions.insert(id.clone(), tx_hex);
+                        (201, json!({ "id": id }))
+                    }
+                    Err(err) => (400, json!({ "error": err })),
+                }
+            }
+            ("GET", ["chain", _, "transaction", txid]) => match self.transactions.get(*txid) {
+                Some(tx_hex) => (200, json!({ "hex": tx_hex })),
+                None => not_found(),
+            },
+            _ => not_found(),
+        }
+    }
+
+    fn route_cooperative(&mut self, method: &str, path: &str, body: &str) -> (u16, Value) {
+        let parts: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+        match (method, parts.as_slice()) {
+            ("GET", ["swap", "submarine", id, "claim"]) => {
+                let Some(swap) = self.swaps.get(*id) else {
+                    return not_found();
+                };
+                let (Some(preimage), Some(lockup)) = (&swap.preimage, &swap.user_lockup) else {
+                    return (400, json!({ "error": "swap is not eligible for a cooperative claim" }));
+                };
+                match submarine_claim(&self.server_keys, lockup, preimage) {
+                    Ok((signer, details)) => {
+                        self.claim_signers.insert(id.to_string(), signer);
+                        (200, details)
+                    }
+                    Err(err) => (400, json!({ "error": err })),
+                }
+            }
+            ("POST", ["swap", "submarine", id, "claim"]) => {
+                let Some(signer) = self.claim_signers.remove(*id) else {
+                    return (400, json!({ "error": "no claim details were requested" }));
+                };
+                if let Err(err) = verify_submarine_claim(&self.server_keys, signer, body) {
+                    return (400, json!({ "error": err }));
+                }
+                if let Some(swap) = self.swaps.get_mut(*id) {
+                    swap.status = SwapStatus::TransactionClaimed.as_str().to_string();
+                }
+                self.publish(id);
+                (200, json!({}))
+            }
+            ("POST", ["swap", "submarine" | "chain", id, "refund"]) => {
+                let Some(swap) = self.swaps.get(*id) else {
+                    return not_found();
+                };
+                // like Boltz, only failed swaps are refunded cooperatively
+                if !SwapStatus::from(swap.status.as_str()).requires_refund() {
+                    return (400, json!({ "error": "swap is not eligible for a cooperative refund" }));
+                }
+                let Some(lockup) = &swap.user_lockup else {
+                    return not_found();
+                };
+                signing_response(
+                    refund_request(body).and_then(|to_sign| partial_signature(&self.server_keys, lockup, &to_sign)),
+                )
+            }
+            ("POST", ["swap", kind @ ("reverse" | "chain"), id, "claim"]) => {
+                let Some(swap) = self.swaps.get(*id) else {
+                    return not_found();
+                };
+                let Some(lockup) = &swap.server_lockup else {
+                    return not_found();
+                };
+                signing_response(
+                    claim_request(*kind == "chain", &swap.preimage_hash, body)
+                        .and_then(|to_sign| partial_signature(&self.server_keys, lockup, &to_sign)),
+                )
+            }
+            _ => not_found(),
+        }
+    }
+
+    fn route_lockup(&self, path: &str) -> (u16, Value) {
+        let parts: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+        let (kind, swap, endpoint) = match parts.as_slice() {
+            ["swap", kind, id, endpoint] => match self.swaps.get(*id) {
+                Some(swap) => (*kind, swap, *endpoint),
+                None => return not_found(),
+            },
+            _ => return not_found(),
+        };
+
+        let lockup = match (kind, endpoint) {
+            ("submarine", "transaction") => swap.user_lockup.as_ref().and_then(Lockup::transaction_json),
+            ("reverse", "transaction") => swap.server_lockup.as_ref().and_then(Lockup::transaction_json),
+            ("chain", "transactions") => {
+                let side = |lockup: &Option<Lockup>| {
+                    lockup.as_ref().and_then(|lockup| {
+                        lockup.transaction_json().map(|tx| {
+                            json!({
+                                "transaction": { "id": tx["id"], "hex": tx["hex"] },
+                                "timeout": { "blockHeight": lockup.timeout_block_height },
+                            })
+                        })
+                    })
+                };
+                Some(json!({ "userLock": side(&swap.user_lockup), "serverLock": side(&swap.server_lockup) }))
+            }
+            _ => return not_found(),
+        };
+
+        match lockup {
+            Some(lockup) => (200, lockup),
+            None => (400, json!({ "error": "no lockup transaction found" })),
+        }
+    }
+
+    fn update_event(&self, swap_ids: &[String]) -> Option<String> {
+        let updates: Vec<Value> = swap_ids
+            .iter()
+            .filter_map(|id| {
+                self.swaps.get(id).map(|swap| {
+                    let mut update = swap.to_json();
+                    update["id"] = json!(id);
+                    update
+                })
+            })
+            .collect();
+        if updates.is_empty() {
+            return None;
+        }
+
+        Some(json!({ "event": "update", "channel": "swap.update", "args": updates }).to_string())
+    }
+
+    fn publish(&mut self, swap_id: &str) {
+        let event = match self.update_event(&[swap_id.to_string()]) {
+            Some(event) => event,
+            None => return,
+        };
+        self.subscribers.retain(|subscriber| {
+            let subscribed = subscriber.swap_ids.lock().expect("subscriber lock poisoned").contains(swap_id);
+            !subscribed || subscriber.sender.send(event.clone()).is_ok()
+        });
+    }
+}
+
+fn not_found() -> (u16, Value) {
+    (404, json!({ "error": "not found" }))
+}
+
+fn signing_response(res: MockResult<Value>) -> (u16, Value) {
+    match res {
+        Ok(signature) => (200, signature),
+        Err(err) => (400, json!({ "error": err })),
+    }
+}
+
+#[derive(Deserialize)]
+struct WsRequest {
+    op: String,
+    channel: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+fn handle_connection(stream: TcpStream, state: Arc<Mutex<State>>, running: Arc<AtomicBool>) -> io::Result<()> {
+    if is_websocket_upgrade(&stream)? {
+        return handle_websocket(stream, state, running);
+    }
+
+    let req = match read_request(&stream)? {
+        Some(req) => req,
+        None => return Ok(()),
+    };
+    let (status, body) = {
+        let mut state = state.lock().expect("mock state lock poisoned");
+        state.requests.push((req.method.clone(), req.path.clone(), req.body.clone()));
+        state.route(&req)
+    };
+
+    write_response(&stream, status, &body.to_string())
+}
+
+fn handle_websocket(stream: TcpStream, state: Arc<Mutex<State>>, running: Arc<AtomicBool>) -> io::Result<()> {
+    let mut socket = tungstenite::accept(stream).map_err(|err| io::Error::other(err.to_string()))?;
+    socket.get_ref().set_read_timeout(Some(POLL_INTERVAL))?;
+
+    let (sender, receiver) = channel();
+    let swap_ids = Arc::new(Mutex::new(HashSet::new()));
+    state.lock().expect("mock state lock poisoned").subscribers.push(Subscriber {
+        swap_ids: swap_ids.clone(),
+        sender,
+    });
+
+    while running.load(Ordering::SeqCst) {
+        while let Ok(event) = receiver.try_recv() {
+            if socket.send(Message::Text(event)).is_err() {
+                return Ok(());
+            }
+        }
+
+        let text = match socket.read() {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => return Ok(()),
+            Ok(_) => continue,
+            Err(tungstenite::Error::Io(err))
+                if matches!(err.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) =>
+            {
+                continue
+            }
+            Err(_) => return Ok(()),
+        };
+
+        let req: WsRequest = match serde_json::from_str(&text) {
+            Ok(req) => req,
+            Err(err) => {
+                let error = json!({ "event": "error", "error": err.to_string() });
+                let _ = socket.send(Message::Text(error.to_string()));
+                continue;
+            }
+        };
+        if req.op != "subscribe" || req.channel != "swap.update" {
+            let error = json!({ "event": "error", "error": format!("unsupported operation {} on {}", req.op, req.channel) });
+            let _ = socket.send(Message::Text(error.to_string()));
+            continue;
+        }
+
+        swap_ids.lock().expect("subscriber lock poisoned").extend(req.args.iter().cloned());
+        let ack = json!({ "event": "subscribe", "channel": "swap.update", "args": req.args });
+        let update = state.lock().expect("mock state lock poisoned").update_event(&req.args);
+        for event in std::iter::once(ack.to_string()).chain(update) {
+            if socket.send(Message::Text(event)).is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    let _ = socket.close(None);
+    let _ = socket.flush();
+    Ok(())
+}
+
+fn serve(listener: TcpListener, state: Arc<Mutex<State>>, running: Arc<AtomicBool>) {
+    while running.load(Ordering::SeqCst) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if stream.set_nonblocking(false).is_err() {
+                    continue;
+                }
+                let state = state.clone();
+                let running = running.clone();
+                spawn(move || handle_connection(stream, state, running));
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => sleep(POLL_INTERVAL),
+            Err(_) => break,
+        }
+    }
+}
+
+#[pyclass]
+pub struct MockBoltzServer {
+    state: Arc<Mutex<State>>,
+    running: Arc<AtomicBool>,
+    port: Option<u16>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockBoltzServer {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("mock state lock poisoned")
+    }
+}
+
+#[pymethods]
+impl MockBoltzServer {
+    #[new]
+    #[pyo3(signature = (network="regtest".to_string()))]
+    pub fn new(network: String) -> PyResult<Self> {
+        parse_chain("BTC", &network)?;
+
+        Ok(MockBoltzServer {
+            state: Arc::new(Mutex::new(State {
+                network,
+                server_keys: Keypair::new(&Secp256k1::new(), &mut thread_rng()),
+                heights: CURRENCIES.iter().map(|currency| (currency.to_string(), 1_000)).collect(),
+                swaps: HashMap::new(),
+                claim_signers: HashMap::new(),
+                transactions: HashMap::new(),
+                responses: HashMap::new(),
+                requests: Vec::new(),
+                subscribers: Vec::new(),
+            })),
+            running: Arc::new(AtomicBool::new(false)),
+            port: None,
+            handle: None,
+        })
+    }
+
+    #[pyo3(signature = (port=0))]
+    pub fn start(&mut self, port: u16) -> PyResult<()> {
+        if self.handle.is_some() {
+            return Err(new_python_error::<BoltzError>("mock server is already running"));
+        }
+
+        let listener = handle_rust_error_as::<NetworkError, _, _>(
+            "could not bind mock server",
+            TcpListener::bind(("127.0.0.1", port)),
+        )?;
+        handle_rust_error_as::<NetworkError, _, _>("could not configure mock server", listener.set_nonblocking(true))?;
+        let port = handle_rust_error_as::<NetworkError, _, _>("could not bind mock server", listener.local_addr())?.port();
+
+        self.running.store(true, Ordering::SeqCst);
+        let state = self.state.clone();
+        let running = self.running.clone();
+        self.handle = Some(spawn(move || serve(listener, state, running)));
+        self.port = Some(port);
+        Ok(())
+    }
+
+    pub fn stop(&mut self, py: Python) {
+        self.running.store(false, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            py.allow_threads(|| {
+                let _ = handle.join();
+            });
+        }
+        self.state().subscribers.clear();
+        self.port = None;
+    }
+
+    fn __enter__(mut slf: PyRefMut<Self>) -> PyResult<PyRefMut<Self>> {
+        if slf.handle.is_none() {
+            slf.start(0)?;
+        }
+        Ok(slf)
+    }
+
+    fn __exit__(&mut self, py: Python, _exc_type: PyObject, _exc_value: PyObject, _traceback: PyObject) {
+        self.stop(py);
+    }
+
+    #[getter]
+    pub fn url(&self) -> PyResult<String> {
+        match self.port {
+            Some(port) => Ok(format!("http://127.0.0.1:{}{}", port, API_PREFIX)),
+            None => Err(new_python_error::<BoltzError>("mock server is not running")),
+        }
+    }
+
+    #[getter]
+    pub fn ws_url(&self) -> PyResult<String> {
+        Ok(ws_url(&self.url()?))
+    }
+
+    #[getter]
+    pub fn server_public_key(&self) -> Vec<u8> {
+        self.state().server_keys.public_key().serialize().to_vec()
+    }
+
+    pub fn set_height(&self, currency: String, height: u32) -> PyResult<()> {
+        if !CURRENCIES.contains(&currency.as_str()) {
+            return Err(PyValueError::new_err(format!("unknown currency {}", currency)));
+        }
+        self.state().heights.insert(currency, height);
+        Ok(())
+    }
+
+    pub fn set_response(&self, method: String, path: String, status: u16, body: String) {
+        self.state().responses.insert((method.to_uppercase(), path), (status, body));
+    }
+
+    pub fn clear_responses(&self) {
+        self.state().responses.clear();
+    }
+
+    pub fn requests(&self) -> Vec<(String, String, String)> {
+        self.state().requests.clone()
+    }
+
+    #[pyo3(signature = (
+        swap_id,
+        status,
+        transaction_id=None,
+        transaction_hex=None,
+        zero_conf_rejected=None,
+        failure_reason=None,
+    ))]
+    pub fn push_status(
+        &self,
+        swap_id: String,
+        status: SwapStatus,
+        transaction_id: Option<String>,
+        transaction_hex: Option<String>,
+        zero_conf_rejected: Option<bool>,
+        failure_reason: Option<String>,
+    ) -> PyResult<()> {
+        let mut state = self.state();
+        let swap = state
+            .swaps
+            .get_mut(&swap_id)
+            .ok_or_else(|| new_python_error::<BoltzError>(&format!("unknown swap {}", swap_id)))?;
+        swap.status = status.as_str().to_string();
+        swap.zero_conf_rejected = zero_conf_rejected;
+        swap.failure_reason = failure_reason;
+        if transaction_id.is_some() {
+            swap.transaction = transaction_id.map(|id| (id, transaction_hex));
+        }
+
+        state.publish(&swap_id);
+        Ok(())
+    }
+
+    #[pyo3(signature = (swap_id, amount=None, server_lockup=None))]
+    pub fn fund_swap(&self, swap_id: String, amount: Option<u64>, server_lockup: Option<bool>) -> PyResult<String> {
+        let state = self.state();
+        let swap = state
+            .swaps
+            .get(&swap_id)
+            .ok_or_else(|| new_python_error::<BoltzError>(&format!("unknown swap {}", swap_id)))?;
+
+        let server_lockup = server_lockup.unwrap_or(swap.user_lockup.is_none());
+        let lockup = if server_lockup { swap.server_lockup.as_ref() } else { swap.user_lockup.as_ref() }
+            .ok_or_else(|| new_python_error::<BoltzError>("swap has no lockup on that side"))?;
+
+        let (txid, tx_hex) =
+            funding_transaction(lockup, amount.unwrap_or(lockup.amount)).map_err(|err| new_python_error::<BoltzError>(&err))?;
+        // chain swaps report the lockup of the server separately
+        let status = if server_lockup && swap.user_lockup.is_some() {
+            SwapStatus::TransactionServerMempool
+        } else {
+            SwapStatus::TransactionMempool
+        };
+        drop(state);
+
+        let mut state = self.state();
+        state.transactions.insert(txid.clone(), tx_hex.clone());
+        if let Some(swap) = state.swaps.get_mut(&swap_id) {
+            let lockup = if server_lockup { swap.server_lockup.as_mut() } else { swap.user_lockup.as_mut() };
+            if let Some(lockup) = lockup {
+                lockup.transaction = Some((txid.clone(), tx_hex.clone()));
+            }
+        }
+        drop(state);
+
+        self.push_status(swap_id, status, Some(txid), Some(tx_hex.clone()), None, None)?;
+        Ok(tx_hex)
+    }
+
+    /// Reveals the preimage of the invoice of a submarine swap like paying it
+    /// would, so that the swap can be claimed cooperatively.
+    pub fn pay_invoice(&self, swap_id: String, preimage: Vec<u8>) -> PyResult<()> {
+        let mut state = self.state();
+        let swap = state
+            .swaps
+            .get_mut(&swap_id)
+            .ok_or_else(|| new_python_error::<BoltzError>(&format!("unknown swap {}", swap_id)))?;
+        if sha256::Hash::hash(preimage.as_slice()) != swap.preimage_hash {
+            return Err(new_python_error::<InvalidInvoiceError>("preimage does not match invoice payment hash"));
+        }
+        swap.preimage = Some(preimage);
+        drop(state);
+
+        self.push_status(swap_id, SwapStatus::TransactionClaimPending, None, None, None, None)
+    }
+
+    pub fn create_invoice(&self, amount_sat: u64, payment_hash: Vec<u8>) -> PyResult<String> {
+        let payment_hash = handle_rust_error("could not parse payment hash", sha256::Hash::from_slice(payment_hash.as_slice()))?;
+        let state = self.state();
+        create_invoice(&state.network, &state.server_keys, amount_sat, payment_hash).map_err(|err| new_python_error::<InvalidInvoiceError>(&err))
+    }
+}
+
+impl Drop for MockBoltzServer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
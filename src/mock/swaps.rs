@@ -0,0 +1,856 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use bitcoin::absolute::LockTime;
+use bitcoin::bip32::{ChildNumber, Xpub};
+use bitcoin::hashes::{hash160, ripemd160, sha256, Hash};
+use bitcoin::key::rand::{thread_rng, Rng, RngCore};
+use bitcoin::opcodes::all::{OP_CHECKSIG, OP_CHECKSIGVERIFY, OP_CLTV, OP_EQUALVERIFY, OP_HASH160, OP_SIZE};
+use bitcoin::script::Builder;
+use bitcoin::secp256k1::{Keypair, PublicKey, Secp256k1, SecretKey, XOnlyPublicKey};
+use bitcoin::transaction::Version;
+use bitcoin::{Address, Amount, Denomination, OutPoint, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness};
+use boltz_client::boltz::{
+    ChainSwapDetails, CreateChainResponse, CreateReverseResponse, CreateSubmarineResponse, Leaf, Side, SwapTree,
+};
+use boltz_client::elements::confidential::{AssetBlindingFactor, ValueBlindingFactor};
+use boltz_client::elements::{self, AddressParams, AssetId, TxOutSecrets};
+use boltz_client::network::Chain;
+use boltz_client::Bolt11Invoice;
+use lightning_invoice::{Currency, InvoiceBuilder, PaymentSecret};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::utils::network::parse_chain;
+
+pub(crate) const CURRENCIES: [&str; 2] = ["BTC", "L-BTC"];
+
+const MINIMAL: u64 = 1_000;
+const MAXIMAL: u64 = 25_000_000;
+const SUBMARINE_PERCENTAGE: f64 = 0.1;
+const REVERSE_PERCENTAGE: f64 = 0.25;
+const CHAIN_PERCENTAGE: f64 = 0.1;
+
+// policy assets of Liquid, its testnet and the regtest of Boltz
+const LIQUID_ASSET: &str = "6f0279e9ed041c3d710a9f57d0c02928416460c4b722ae3457a11eec381c526d";
+const LIQUID_TESTNET_ASSET: &str = "144c654344aa716d6f3abcc1ca90e5641e4e2a7f633bc09fe3baf64585819a49";
+const LIQUID_REGTEST_ASSET: &str = "5ac9f65c0efcc4775e0baec4ec03abdde22473cd3cf33c0419ca290e0751b225";
+
+const BTC_LEAF_VERSION: u8 = 0xc0;
+const LIQUID_LEAF_VERSION: u8 = 0xc4;
+
+// Number of unhardened children of an xpub searched for swap keys
+const RESTORE_GAP_LIMIT: u32 = 100;
+
+pub(crate) type MockResult<T> = Result<T, String>;
+
+#[derive(Debug, Clone)]
+pub(crate) struct Lockup {
+    pub currency: String,
+    pub address: String,
+    pub amount: u64,
+    pub timeout_block_height: u32,
+    // ID and hex of the transaction that funded the lockup address
+    pub transaction: Option<(String, String)>,
+    // only BTC lockups can be spent cooperatively with the mock server
+    pub script: Option<boltz_client::BtcSwapScript>,
+}
+
+impl Lockup {
+    pub fn transaction_json(&self) -> Option<Value> {
+        self.transaction
+            .as_ref()
+            .map(|(id, hex)| json!({ "id": id, "hex": hex, "timeoutBlockHeight": self.timeout_block_height }))
+    }
+}
+
+// Entry of the swap restore endpoint, the details of a side are listed with
+// their key index when the key of the user derives from the xpub
+#[derive(Debug, Clone)]
+pub(crate) struct Restorable {
+    pub kind: &'static str,
+    pub from: String,
+    pub to: String,
+    pub details: Vec<(&'static str, PublicKey, Value)>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct MockSwap {
+    pub status: String,
+    // payment hash of the invoice of submarine swaps
+    pub preimage_hash: sha256::Hash,
+    // revealed when the mock server pays the invoice of a submarine swap
+    pub preimage: Option<Vec<u8>>,
+    pub zero_conf_rejected: Option<bool>,
+    pub failure_reason: Option<String>,
+    pub transaction: Option<(String, Option<String>)>,
+    // funds sent by the user and by the server respectively
+    pub user_lockup: Option<Lockup>,
+    pub server_lockup: Option<Lockup>,
+    pub restorable: Restorable,
+}
+
+impl MockSwap {
+    fn new(
+        status: &str,
+        preimage_hash: sha256::Hash,
+        user_lockup: Option<Lockup>,
+        server_lockup: Option<Lockup>,
+        restorable: Restorable,
+    ) -> Self {
+        MockSwap {
+            status: status.to_string(),
+            preimage_hash,
+            preimage: None,
+            zero_conf_rejected: None,
+            failure_reason: None,
+            transaction: None,
+            user_lockup,
+            server_lockup,
+            restorable,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut status = json!({ "status": self.status });
+        if let Some((id, hex)) = &self.transaction {
+            status["transaction"] = json!({ "id": id, "hex": hex });
+        }
+        if let Some(zero_conf_rejected) = self.zero_conf_rejected {
+            status["zeroConfRejected"] = json!(zero_conf_rejected);
+        }
+        if let Some(failure_reason) = &self.failure_reason {
+            status["failureReason"] = json!(failure_reason);
+        }
+        status
+    }
+}
+
+#[derive(Deserialize)]
+struct RestoreRequest {
+    xpub: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubmarineRequest {
+    from: String,
+    to: String,
+    invoice: String,
+    refund_public_key: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReverseRequest {
+    from: String,
+    to: String,
+    preimage_hash: String,
+    claim_public_key: String,
+    invoice_amount: Option<u64>,
+    onchain_amount: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChainRequest {
+    from: String,
+    to: String,
+    preimage_hash: String,
+    claim_public_key: String,
+    refund_public_key: String,
+    user_lock_amount: Option<u64>,
+    server_lock_amount: Option<u64>,
+}
+
+fn miner_fee(currency: &str) -> u64 {
+    match currency {
+        "L-BTC" => 30,
+        _ => 1_000,
+    }
+}
+
+fn timeout_delta(currency: &str) -> u32 {
+    match currency {
+        "L-BTC" => 1_440,
+        _ => 144,
+    }
+}
+
+fn percentage_fee(amount: u64, percentage: f64) -> u64 {
+    (amount as f64 * percentage / 100.0).ceil() as u64
+}
+
+fn check_currency(currency: &str) -> MockResult<()> {
+    if CURRENCIES.contains(&currency) {
+        Ok(())
+    } else {
+        Err(format!("unknown currency {}", currency))
+    }
+}
+
+fn check_limits(amount: u64) -> MockResult<()> {
+    if amount < MINIMAL {
+        return Err(format!("{} is less than minimal of {}", amount, MINIMAL));
+    }
+    if amount > MAXIMAL {
+        return Err(format!("{} is more than maximal of {}", amount, MAXIMAL));
+    }
+    Ok(())
+}
+
+fn pair_hash(from: &str, to: &str, kind: &str) -> String {
+    sha256::Hash::hash(format!("{}/{}/{}", kind, from, to).as_bytes()).to_string()
+}
+
+pub(crate) fn submarine_pairs() -> Value {
+    let mut pairs = json!({});
+    for from in CURRENCIES {
+        pairs[from] = json!({
+            "BTC": {
+                "hash": pair_hash(from, "BTC", "submarine"),
+                "rate": 1.0,
+                "limits": { "maximal": MAXIMAL, "minimal": MINIMAL, "maximalZeroConf": 0 },
+                "fees": { "percentage": SUBMARINE_PERCENTAGE, "minerFees": miner_fee(from) },
+            }
+        });
+    }
+    pairs
+}
+
+pub(crate) fn reverse_pairs() -> Value {
+    let mut to_pairs = json!({});
+    for to in CURRENCIES {
+        to_pairs[to] = json!({
+            "hash": pair_hash("BTC", to, "reverse"),
+            "rate": 1.0,
+            "limits": { "maximal": MAXIMAL, "minimal": MINIMAL },
+            "fees": {
+                "percentage": REVERSE_PERCENTAGE,
+                "minerFees": { "lockup": miner_fee(to), "claim": miner_fee(to) },
+            },
+        });
+    }
+    json!({ "BTC": to_pairs })
+}
+
+pub(crate) fn chain_pairs() -> Value {
+    let mut pairs = json!({});
+    for from in CURRENCIES {
+        let mut to_pairs = json!({});
+        for to in CURRENCIES.iter().filter(|to| **to != from) {
+            to_pairs[*to] = json!({
+                "hash": pair_hash(from, to, "chain"),
+                "rate": 1.0,
+                "limits": { "maximal": MAXIMAL, "minimal": MINIMAL, "maximalZeroConf": 0 },
+                "fees": {
+                    "percentage": CHAIN_PERCENTAGE,
+                    "minerFees": {
+                        "server": miner_fee(to),
+                        "user": { "claim": miner_fee(to), "lockup": miner_fee(from) },
+                    },
+                },
+            });
+        }
+        pairs[from] = to_pairs;
+    }
+    pairs
+}
+
+fn swap_id() -> String {
+    let mut rng = thread_rng();
+    (0..12)
+        .map(|_| char::from(b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789"[rng.gen_range(0..62)]))
+        .collect()
+}
+
+fn parse_body<T: for<'de> Deserialize<'de>>(body: &str) -> MockResult<T> {
+    serde_json::from_str(body).map_err(|err| format!("invalid request: {}", err))
+}
+
+fn parse_key(public_key: &str) -> MockResult<PublicKey> {
+    PublicKey::from_str(public_key).map_err(|err| format!("invalid public key: {}", err))
+}
+
+fn parse_boltz_key(public_key: &PublicKey) -> boltz_client::PublicKey {
+    boltz_client::PublicKey::from_slice(&public_key.serialize()).expect("secp256k1 keys are valid public keys")
+}
+
+fn parse_preimage_hash(preimage_hash: &str) -> MockResult<sha256::Hash> {
+    sha256::Hash::from_str(preimage_hash).map_err(|err| format!("invalid preimage hash: {}", err))
+}
+
+fn parse_hashlock(preimage_hash: &str) -> MockResult<hash160::Hash> {
+    let preimage_hash = parse_preimage_hash(preimage_hash)?;
+    Ok(hash160::Hash::from_byte_array(
+        ripemd160::Hash::hash(preimage_hash.as_byte_array()).to_byte_array(),
+    ))
+}
+
+fn leaf(currency: &str, script: ScriptBuf) -> Leaf {
+    Leaf {
+        output: script.to_hex_string(),
+        version: if currency == "L-BTC" { LIQUID_LEAF_VERSION } else { BTC_LEAF_VERSION },
+    }
+}
+
+// Same templates the Boltz backend uses, so the library can parse the trees
+fn submarine_tree(currency: &str, hashlock: hash160::Hash, claim_key: XOnlyPublicKey, refund_key: XOnlyPublicKey, timeout: u32) -> SwapTree {
+    let claim = Builder::new()
+        .push_opcode(OP_HASH160)
+        .push_slice(hashlock.to_byte_array())
+        .push_opcode(OP_EQUALVERIFY)
+        .push_x_only_key(&claim_key)
+        .push_opcode(OP_CHECKSIG)
+        .into_script();
+
+    SwapTree {
+        claim_leaf: leaf(currency, claim),
+        refund_leaf: leaf(currency, refund_script(refund_key, timeout)),
+    }
+}
+
+fn reverse_tree(currency: &str, hashlock: hash160::Hash, claim_key: XOnlyPublicKey, refund_key: XOnlyPublicKey, timeout: u32) -> SwapTree {
+    let claim = Builder::new()
+        .push_opcode(OP_SIZE)
+        .push_int(32)
+        .push_opcode(OP_EQUALVERIFY)
+        .push_opcode(OP_HASH160)
+        .push_slice(hashlock.to_byte_array())
+        .push_opcode(OP_EQUALVERIFY)
+        .push_x_only_key(&claim_key)
+        .push_opcode(OP_CHECKSIG)
+        .into_script();
+
+    SwapTree {
+        claim_leaf: leaf(currency, claim),
+        refund_leaf: leaf(currency, refund_script(refund_key, timeout)),
+    }
+}
+
+fn refund_script(refund_key: XOnlyPublicKey, timeout: u32) -> ScriptBuf {
+    Builder::new()
+        .push_x_only_key(&refund_key)
+        .push_opcode(OP_CHECKSIGVERIFY)
+        .push_lock_time(LockTime::from_height(timeout).expect("timeout is a block height"))
+        .push_opcode(OP_CLTV)
+        .into_script()
+}
+
+fn chain(currency: &str, network: &str) -> MockResult<Chain> {
+    parse_chain(currency, network).map_err(|_| format!("unknown currency {}", currency))
+}
+
+fn blinding_key(currency: &str) -> Option<String> {
+    match currency {
+        "L-BTC" => Some(hex::encode(SecretKey::new(&mut thread_rng()).secret_bytes())),
+        _ => None,
+    }
+}
+
+fn submarine_lockup(currency: &str, network: &str, response: &CreateSubmarineResponse, our_pubkey: boltz_client::PublicKey) -> MockResult<(String, Option<boltz_client::BtcSwapScript>)> {
+    let chain = chain(currency, network)?;
+    let lockup = match currency {
+        "L-BTC" => boltz_client::LBtcSwapScript::submarine_from_swap_resp(response, our_pubkey)
+            .and_then(|script| script.to_address(chain))
+            .map(|address| (address.to_string(), None)),
+        _ => boltz_client::BtcSwapScript::submarine_from_swap_resp(response, our_pubkey)
+            .and_then(|script| Ok((script.to_address(chain)?.to_string(), Some(script)))),
+    };
+    lockup.map_err(|err| format!("could not compute lockup address: {:?}", err))
+}
+
+fn reverse_lockup(currency: &str, network: &str, response: &CreateReverseResponse, our_pubkey: boltz_client::PublicKey) -> MockResult<(String, Option<boltz_client::BtcSwapScript>)> {
+    let chain = chain(currency, network)?;
+    let lockup = match currency {
+        "L-BTC" => boltz_client::LBtcSwapScript::reverse_from_swap_resp(response, our_pubkey)
+            .and_then(|script| script.to_address(chain))
+            .map(|address| (address.to_string(), None)),
+        _ => boltz_client::BtcSwapScript::reverse_from_swap_resp(response, our_pubkey)
+            .and_then(|script| Ok((script.to_address(chain)?.to_string(), Some(script)))),
+    };
+    lockup.map_err(|err| format!("could not compute lockup address: {:?}", err))
+}
+
+fn chain_lockup(currency: &str, network: &str, side: Side, details: &ChainSwapDetails, our_pubkey: boltz_client::PublicKey) -> MockResult<(String, Option<boltz_client::BtcSwapScript>)> {
+    let chain = chain(currency, network)?;
+    let lockup = match currency {
+        "L-BTC" => boltz_client::LBtcSwapScript::chain_from_swap_resp(side, details.clone(), our_pubkey)
+            .and_then(|script| script.to_address(chain))
+            .map(|address| (address.to_string(), None)),
+        _ => boltz_client::BtcSwapScript::chain_from_swap_resp(side, details.clone(), our_pubkey)
+            .and_then(|script| Ok((script.to_address(chain)?.to_string(), Some(script)))),
+    };
+    lockup.map_err(|err| format!("could not compute lockup address: {:?}", err))
+}
+
+fn bip21(currency: &str, address: &str, amount: u64) -> String {
+    let scheme = if currency == "L-BTC" { "liquidnetwork" } else { "bitcoin" };
+    format!(
+        "{}:{}?amount={}",
+        scheme,
+        address,
+        Amount::from_sat(amount).to_string_in(Denomination::Bitcoin)
+    )
+}
+
+pub(crate) fn create_submarine(network: &str, server_keys: &Keypair, height: impl Fn(&str) -> u32, body: &str) -> MockResult<(MockSwap, Value)> {
+    let req: SubmarineRequest = parse_body(body)?;
+    check_currency(&req.from)?;
+    if req.to != "BTC" {
+        return Err(format!("could not find pair {}/{}", req.from, req.to));
+    }
+
+    let invoice = Bolt11Invoice::from_str(&req.invoice).map_err(|err| format!("invalid invoice: {}", err))?;
+    let invoice_amount = invoice
+        .amount_milli_satoshis()
+        .ok_or_else(|| "invoice has no amount".to_string())?
+        .div_ceil(1000);
+    check_limits(invoice_amount)?;
+    let expected_amount = invoice_amount + percentage_fee(invoice_amount, SUBMARINE_PERCENTAGE) + miner_fee(&req.from);
+
+    let refund_key = parse_key(&req.refund_public_key)?;
+    let hashlock = hash160::Hash::from_byte_array(ripemd160::Hash::hash(invoice.payment_hash().as_byte_array()).to_byte_array());
+    let timeout = height(&req.from) + timeout_delta(&req.from);
+
+    let mut response = CreateSubmarineResponse {
+        id: swap_id(),
+        bip21: String::new(),
+        address: String::new(),
+        expected_amount,
+        accept_zero_conf: false,
+        referral_id: None,
+        timeout_block_height: timeout as u64,
+        claim_public_key: parse_boltz_key(&server_keys.public_key()),
+        blinding_key: blinding_key(&req.from),
+        swap_tree: submarine_tree(&req.from, hashlock, server_keys.x_only_public_key().0, refund_key.x_only_public_key().0, timeout),
+    };
+    let (address, script) = submarine_lockup(&req.from, network, &response, parse_boltz_key(&refund_key))?;
+    response.address = address;
+    response.bip21 = bip21(&req.from, &response.address, expected_amount);
+
+    let swap = MockSwap::new(
+        "invoice.set",
+        *invoice.payment_hash(),
+        Some(Lockup {
+            currency: req.from.clone(),
+            address: response.address.clone(),
+            amount: expected_amount,
+            timeout_block_height: timeout,
+            transaction: None,
+            script,
+        }),
+        None,
+        Restorable {
+            kind: "submarine",
+            from: req.from.clone(),
+            to: req.to.clone(),
+            details: vec![(
+                "refundDetails",
+                refund_key,
+                restore_details(&response.swap_tree, &response.address, server_keys, timeout, &response.blinding_key, expected_amount),
+            )],
+        },
+    );
+
+    Ok((
+        swap,
+        json!({
+            "id": response.id,
+            "bip21": response.bip21,
+            "address": response.address,
+            "swapTree": tree_json(&response.swap_tree),
+            "claimPublicKey": server_keys.public_key().to_string(),
+            "timeoutBlockHeight": response.timeout_block_height,
+            "acceptZeroConf": response.accept_zero_conf,
+            "expectedAmount": response.expected_amount,
+            "blindingKey": response.blinding_key,
+        }),
+    ))
+}
+
+pub(crate) fn create_reverse(network: &str, server_keys: &Keypair, height: impl Fn(&str) -> u32, body: &str) -> MockResult<(MockSwap, Value)> {
+    let req: ReverseRequest = parse_body(body)?;
+    check_currency(&req.to)?;
+    if req.from != "BTC" {
+        return Err(format!("could not find pair {}/{}", req.from, req.to));
+    }
+
+    let fees = 2 * miner_fee(&req.to);
+    let (invoice_amount, onchain_amount) = match (req.invoice_amount, req.onchain_amount) {
+        (Some(invoice_amount), None) => {
+            let onchain_amount = invoice_amount
+                .checked_sub(percentage_fee(invoice_amount, REVERSE_PERCENTAGE) + fees)
+                .ok_or_else(|| "invoice amount does not cover the fees".to_string())?;
+            (invoice_amount, onchain_amount)
+        }
+        (None, Some(onchain_amount)) => {
+            let invoice_amount = ((onchain_amount + fees) as f64 / (1.0 - REVERSE_PERCENTAGE / 100.0)).ceil() as u64;
+            (invoice_amount, onchain_amount)
+        }
+        _ => return Err("either invoiceAmount or onchainAmount has to be set".to_string()),
+    };
+    check_limits(invoice_amount)?;
+
+    let preimage_hash = parse_preimage_hash(&req.preimage_hash)?;
+    let claim_key = parse_key(&req.claim_public_key)?;
+    let timeout = height(&req.to) + timeout_delta(&req.to);
+
+    let mut response = CreateReverseResponse {
+        id: swap_id(),
+        invoice: create_invoice(network, server_keys, invoice_amount, preimage_hash)?,
+        swap_tree: reverse_tree(&req.to, parse_hashlock(&req.preimage_hash)?, claim_key.x_only_public_key().0, server_keys.x_only_public_key().0, timeout),
+        lockup_address: String::new(),
+        refund_public_key: parse_boltz_key(&server_keys.public_key()),
+        timeout_block_height: timeout,
+        onchain_amount,
+        blinding_key: blinding_key(&req.to),
+    };
+    let (lockup_address, script) = reverse_lockup(&req.to, network, &response, parse_boltz_key(&claim_key))?;
+    response.lockup_address = lockup_address;
+
+    let swap = MockSwap::new(
+        "swap.created",
+        preimage_hash,
+        None,
+        Some(Lockup {
+            currency: req.to.clone(),
+            address: response.lockup_address.clone(),
+            amount: onchain_amount,
+            timeout_block_height: timeout,
+            transaction: None,
+            script,
+        }),
+        Restorable {
+            kind: "reverse",
+            from: req.from.clone(),
+            to: req.to.clone(),
+            details: vec![(
+                "claimDetails",
+                claim_key,
+                restore_details(&response.swap_tree, &response.lockup_address, server_keys, timeout, &response.blinding_key, onchain_amount),
+            )],
+        },
+    );
+
+    Ok((
+        swap,
+        json!({
+            "id": response.id,
+            "invoice": response.invoice,
+            "swapTree": tree_json(&response.swap_tree),
+            "lockupAddress": response.lockup_address,
+            "refundPublicKey": server_keys.public_key().to_string(),
+            "timeoutBlockHeight": response.timeout_block_height,
+            "onchainAmount": response.onchain_amount,
+            "blindingKey": response.blinding_key,
+        }),
+    ))
+}
+
+pub(crate) fn create_chain(network: &str, server_keys: &Keypair, height: impl Fn(&str) -> u32, body: &str) -> MockResult<(MockSwap, Value)> {
+    let req: ChainRequest = parse_body(body)?;
+    check_currency(&req.from)?;
+    check_currency(&req.to)?;
+    if req.from == req.to {
+        return Err(format!("could not find pair {}/{}", req.from, req.to));
+    }
+
+    let fees = miner_fee(&req.to);
+    let (user_lock_amount, server_lock_amount) = match (req.user_lock_amount, req.server_lock_amount) {
+        (Some(user_lock_amount), None) => {
+            let server_lock_amount = user_lock_amount
+                .checked_sub(percentage_fee(user_lock_amount, CHAIN_PERCENTAGE) + fees)
+                .ok_or_else(|| "user lock amount does not cover the fees".to_string())?;
+            (user_lock_amount, server_lock_amount)
+        }
+        (None, Some(server_lock_amount)) => {
+            let user_lock_amount = ((server_lock_amount + fees) as f64 / (1.0 - CHAIN_PERCENTAGE / 100.0)).ceil() as u64;
+            (user_lock_amount, server_lock_amount)
+        }
+        _ => return Err("either userLockAmount or serverLockAmount has to be set".to_string()),
+    };
+    check_limits(user_lock_amount)?;
+
+    let preimage_hash = parse_preimage_hash(&req.preimage_hash)?;
+    let hashlock = parse_hashlock(&req.preimage_hash)?;
+    let claim_key = parse_key(&req.claim_public_key)?;
+    let refund_key = parse_key(&req.refund_public_key)?;
+    let server_key = server_keys.x_only_public_key().0;
+
+    // the user locks on the from chain and the server claims with the preimage
+    let lockup_timeout = height(&req.from) + 2 * timeout_delta(&req.from);
+    let mut lockup_details = ChainSwapDetails {
+        swap_tree: reverse_tree(&req.from, hashlock, server_key, refund_key.x_only_public_key().0, lockup_timeout),
+        lockup_address: String::new(),
+        server_public_key: parse_boltz_key(&server_keys.public_key()),
+        timeout_block_height: lockup_timeout,
+        amount: user_lock_amount,
+        blinding_key: blinding_key(&req.from),
+        refund_address: None,
+        claim_address: None,
+        bip21: None,
+    };
+    let (lockup_address, user_script) = chain_lockup(&req.from, network, Side::Lockup, &lockup_details, parse_boltz_key(&refund_key))?;
+    lockup_details.lockup_address = lockup_address;
+    lockup_details.bip21 = Some(bip21(&req.from, &lockup_details.lockup_address, user_lock_amount));
+
+    // the server locks on the to chain and the user claims with the preimage
+    let claim_timeout = height(&req.to) + timeout_delta(&req.to);
+    let mut claim_details = ChainSwapDetails {
+        swap_tree: reverse_tree(&req.to, hashlock, claim_key.x_only_public_key().0, server_key, claim_timeout),
+        lockup_address: String::new(),
+        server_public_key: parse_boltz_key(&server_keys.public_key()),
+        timeout_block_height: claim_timeout,
+        amount: server_lock_amount,
+        blinding_key: blinding_key(&req.to),
+        refund_address: None,
+        claim_address: None,
+        bip21: None,
+    };
+    let (lockup_address, server_script) = chain_lockup(&req.to, network, Side::Claim, &claim_details, parse_boltz_key(&claim_key))?;
+    claim_details.lockup_address = lockup_address;
+
+    let response = CreateChainResponse {
+        id: swap_id(),
+        claim_details,
+        lockup_details,
+    };
+    let swap = MockSwap::new(
+        "swap.created",
+        preimage_hash,
+        Some(Lockup {
+            currency: req.from.clone(),
+            address: response.lockup_details.lockup_address.clone(),
+            amount: user_lock_amount,
+            timeout_block_height: response.lockup_details.timeout_block_height,
+            transaction: None,
+            script: user_script,
+        }),
+        Some(Lockup {
+            currency: req.to.clone(),
+            address: response.claim_details.lockup_address.clone(),
+            amount: server_lock_amount,
+            timeout_block_height: response.claim_details.timeout_block_height,
+            transaction: None,
+            script: server_script,
+        }),
+        Restorable {
+            kind: "chain",
+            from: req.from.clone(),
+            to: req.to.clone(),
+            details: vec![
+                ("refundDetails", refund_key, chain_restore_details(&response.lockup_details, server_keys)),
+                ("claimDetails", claim_key, chain_restore_details(&response.claim_details, server_keys)),
+            ],
+        },
+    );
+
+    Ok((
+        swap,
+        json!({
+            "id": response.id,
+            "claimDetails": details_json(&response.claim_details),
+            "lockupDetails": details_json(&response.lockup_details),
+        }),
+    ))
+}
+
+fn tree_json(tree: &SwapTree) -> Value {
+    json!({
+        "claimLeaf": { "output": tree.claim_leaf.output, "version": tree.claim_leaf.version },
+        "refundLeaf": { "output": tree.refund_leaf.output, "version": tree.refund_leaf.version },
+    })
+}
+
+fn restore_details(tree: &SwapTree, lockup_address: &str, server_keys: &Keypair, timeout: u32, blinding_key: &Option<String>, amount: u64) -> Value {
+    json!({
+        "tree": tree_json(tree),
+        "lockupAddress": lockup_address,
+        "serverPublicKey": server_keys.public_key().to_string(),
+        "timeoutBlockHeight": timeout,
+        "blindingKey": blinding_key,
+        "amount": amount,
+    })
+}
+
+fn chain_restore_details(details: &ChainSwapDetails, server_keys: &Keypair) -> Value {
+    restore_details(
+        &details.swap_tree,
+        &details.lockup_address,
+        server_keys,
+        details.timeout_block_height,
+        &details.blinding_key,
+        details.amount,
+    )
+}
+
+// Lists the swaps whose keys derive from the xpub, like the restore endpoint
+// of Boltz does for the rescue files of the web app
+pub(crate) fn restore_swaps(swaps: &HashMap<String, MockSwap>, body: &str) -> MockResult<Value> {
+    let req: RestoreRequest = parse_body(body)?;
+    let xpub = Xpub::from_str(&req.xpub).map_err(|err| format!("invalid xpub: {}", err))?;
+    let secp = Secp256k1::verification_only();
+
+    let mut indexes = HashMap::new();
+    for index in 0..RESTORE_GAP_LIMIT {
+        let child = ChildNumber::from_normal_idx(index).map_err(|err| format!("invalid key index: {}", err))?;
+        let key = xpub.derive_pub(&secp, &[child]).map_err(|err| format!("could not derive key: {}", err))?;
+        indexes.insert(key.public_key, index);
+    }
+
+    let restored: Vec<Value> = swaps
+        .iter()
+        .filter_map(|(id, swap)| {
+            let restorable = &swap.restorable;
+            let mut entry = json!({
+                "id": id,
+                "type": restorable.kind,
+                "status": swap.status,
+                "from": restorable.from,
+                "to": restorable.to,
+                "createdAt": 0,
+            });
+            let mut found = false;
+            for (name, key, details) in &restorable.details {
+                if let Some(index) = indexes.get(key) {
+                    let mut details = details.clone();
+                    details["keyIndex"] = json!(index);
+                    entry[*name] = details;
+                    found = true;
+                }
+            }
+            found.then_some(entry)
+        })
+        .collect();
+
+    Ok(json!(restored))
+}
+
+fn details_json(details: &ChainSwapDetails) -> Value {
+    json!({
+        "swapTree": tree_json(&details.swap_tree),
+        "lockupAddress": details.lockup_address,
+        "serverPublicKey": details.server_public_key.to_string(),
+        "timeoutBlockHeight": details.timeout_block_height,
+        "amount": details.amount,
+        "blindingKey": details.blinding_key,
+        "bip21": details.bip21,
+    })
+}
+
+pub(crate) fn create_invoice(network: &str, node_keys: &Keypair, amount: u64, payment_hash: sha256::Hash) -> MockResult<String> {
+    let currency = match network {
+        "main" => Currency::Bitcoin,
+        "testnet" => Currency::BitcoinTestnet,
+        _ => Currency::Regtest,
+    };
+
+    let mut payment_secret = [0u8; 32];
+    thread_rng().fill_bytes(&mut payment_secret);
+
+    InvoiceBuilder::new(currency)
+        .description("boltz mock".to_string())
+        .payment_hash(payment_hash)
+        .payment_secret(PaymentSecret(payment_secret))
+        .current_timestamp()
+        .min_final_cltv_expiry_delta(144)
+        .amount_milli_satoshis(amount * 1000)
+        .build_signed(|hash| Secp256k1::new().sign_ecdsa_recoverable(hash, &node_keys.secret_key()))
+        .map(|invoice| invoice.to_string())
+        .map_err(|err| format!("could not create invoice: {}", err))
+}
+
+// Builds a transaction that pays the lockup address from a made up input; it
+// is never broadcast, it only has to be parseable by the transaction builders.
+// Returns the ID and hex of the transaction
+pub(crate) fn funding_transaction(lockup: &Lockup, amount: u64) -> MockResult<(String, String)> {
+    match lockup.currency.as_str() {
+        "L-BTC" => liquid_funding_transaction(&lockup.address, amount)
+            .map(|tx| (tx.txid().to_string(), elements::encode::serialize_hex(&tx))),
+        _ => btc_funding_transaction(&lockup.address, amount)
+            .map(|tx| (tx.compute_txid().to_string(), bitcoin::consensus::encode::serialize_hex(&tx))),
+    }
+}
+
+fn previous_txid() -> [u8; 32] {
+    let mut previous_txid = [0u8; 32];
+    thread_rng().fill_bytes(&mut previous_txid);
+    previous_txid
+}
+
+fn btc_funding_transaction(address: &str, amount: u64) -> MockResult<Transaction> {
+    let address = Address::from_str(address)
+        .map_err(|err| format!("invalid lockup address: {}", err))?
+        .assume_checked();
+
+    Ok(Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint::new(Txid::from_byte_array(previous_txid()), 0),
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(amount),
+            script_pubkey: address.script_pubkey(),
+        }],
+    })
+}
+
+fn policy_asset(params: &AddressParams) -> MockResult<AssetId> {
+    let asset = if *params == AddressParams::LIQUID {
+        LIQUID_ASSET
+    } else if *params == AddressParams::LIQUID_TESTNET {
+        LIQUID_TESTNET_ASSET
+    } else {
+        LIQUID_REGTEST_ASSET
+    };
+    AssetId::from_str(asset).map_err(|err| format!("invalid asset: {}", err))
+}
+
+// The output is blinded to the blinding key of the confidential lockup
+// address, so the swap has to unblind it like a real lockup
+fn liquid_funding_transaction(address: &str, amount: u64) -> MockResult<elements::Transaction> {
+    let address = elements::Address::from_str(address).map_err(|err| format!("invalid lockup address: {}", err))?;
+    let asset = policy_asset(address.params)?;
+
+    // the made up input is explicit, the surjection proof only needs its asset
+    let input = TxOutSecrets::new(asset, AssetBlindingFactor::zero(), amount, ValueBlindingFactor::zero());
+    let (output, ..) = elements::TxOut::new_not_last_confidential(
+        &mut thread_rng(),
+        &elements::secp256k1_zkp::Secp256k1::new(),
+        amount,
+        address,
+        asset,
+        &[input],
+    )
+    .map_err(|err| format!("could not blind lockup output: {:?}", err))?;
+
+    Ok(elements::Transaction {
+        version: 2,
+        lock_time: elements::LockTime::ZERO,
+        input: vec![elements::TxIn {
+            previous_output: elements::OutPoint::new(elements::Txid::from_byte_array(previous_txid()), 0),
+            sequence: elements::Sequence::MAX,
+            ..Default::default()
+        }],
+        output: vec![output],
+    })
+}
+
+// Transactions posted to the mock server are only decoded for their ID
+pub(crate) fn transaction_id(currency: &str, tx_hex: &str) -> MockResult<String> {
+    let tx = hex::decode(tx_hex).map_err(|err| format!("invalid transaction hex: {}", err))?;
+    match currency {
+        "BTC" => bitcoin::consensus::encode::deserialize::<Transaction>(&tx)
+            .map(|tx| tx.compute_txid().to_string())
+            .map_err(|err| format!("invalid transaction: {}", err)),
+        "L-BTC" => boltz_client::elements::encode::deserialize::<boltz_client::elements::Transaction>(&tx)
+            .map(|tx| tx.txid().to_string())
+            .map_err(|err| format!("invalid transaction: {}", err)),
+        _ => Err(format!("unknown currency {}", currency)),
+    }
+}
@@ -0,0 +1,225 @@
+use pyo3::PyResult;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+
+use crate::store::swap_store::{StoredSwap, SwapStatusEntry, SwapStorage, TransactionKind};
+use crate::types::status::SwapStatus;
+use crate::utils::errors::handle_rust_error;
+
+const COULD_NOT_QUERY: &str = "could not query swap store";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS swaps (
+    id TEXT PRIMARY KEY NOT NULL,
+    swap_type TEXT NOT NULL,
+    asset_send TEXT NOT NULL,
+    asset_receive TEXT NOT NULL,
+    status TEXT,
+    data TEXT NOT NULL,
+    timeout_block_height INTEGER,
+    lockup_address TEXT,
+    funding_txid TEXT,
+    claim_txid TEXT,
+    refund_txid TEXT,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS status_history (
+    swap_id TEXT NOT NULL,
+    status TEXT NOT NULL,
+    transaction_id TEXT,
+    created_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS status_history_swap_id ON status_history(swap_id);
+";
+
+const SWAP_COLUMNS: &str = "id, swap_type, asset_send, asset_receive, status, data, timeout_block_height, \
+                            lockup_address, funding_txid, claim_txid, refund_txid, created_at, updated_at";
+
+// Columns are read as they are stored, the status is parsed afterwards since
+// row mappers can only fail with rusqlite errors
+struct SwapRow {
+    swap: StoredSwap,
+    status: Option<String>,
+}
+
+fn swap_row(row: &Row) -> rusqlite::Result<SwapRow> {
+    Ok(SwapRow {
+        swap: StoredSwap {
+            id: row.get(0)?,
+            swap_type: row.get(1)?,
+            asset_send: row.get(2)?,
+            asset_receive: row.get(3)?,
+            status: None,
+            data: row.get(5)?,
+            timeout_block_height: row.get(6)?,
+            lockup_address: row.get(7)?,
+            funding_txid: row.get(8)?,
+            claim_txid: row.get(9)?,
+            refund_txid: row.get(10)?,
+            created_at: row.get(11)?,
+            updated_at: row.get(12)?,
+        },
+        status: row.get(4)?,
+    })
+}
+
+fn into_swap(row: SwapRow) -> PyResult<StoredSwap> {
+    let mut swap = row.swap;
+    swap.status = row.status.map(|status| SwapStatus::from(status.as_str()));
+    Ok(swap)
+}
+
+pub(crate) struct SqliteStorage {
+    conn: Connection,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &str) -> PyResult<Self> {
+        let conn = handle_rust_error("could not open swap store", Connection::open(path))?;
+        handle_rust_error("could not create swap store", conn.execute_batch(SCHEMA))?;
+
+        Ok(SqliteStorage { conn })
+    }
+
+    fn query_swaps(&self, sql: &str, values: Vec<String>) -> PyResult<Vec<StoredSwap>> {
+        let mut stmt = handle_rust_error(COULD_NOT_QUERY, self.conn.prepare(sql))?;
+        let rows = handle_rust_error(
+            COULD_NOT_QUERY,
+            stmt.query_map(params_from_iter(values), swap_row)
+                .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>()),
+        )?;
+
+        rows.into_iter().map(into_swap).collect()
+    }
+}
+
+impl SwapStorage for SqliteStorage {
+    fn insert(&self, swap: &StoredSwap) -> PyResult<()> {
+        handle_rust_error(
+            "could not store swap",
+            self.conn.execute(
+                &format!("INSERT INTO swaps ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)", SWAP_COLUMNS),
+                params![
+                    swap.id,
+                    swap.swap_type,
+                    swap.asset_send,
+                    swap.asset_receive,
+                    swap.status.map(|status| status.as_str()),
+                    swap.data,
+                    swap.timeout_block_height,
+                    swap.lockup_address,
+                    swap.funding_txid,
+                    swap.claim_txid,
+                    swap.refund_txid,
+                    swap.created_at,
+                    swap.updated_at,
+                ],
+            ),
+        )?;
+        Ok(())
+    }
+
+    fn get(&self, swap_id: &str) -> PyResult<Option<StoredSwap>> {
+        let row = handle_rust_error(
+            COULD_NOT_QUERY,
+            self.conn
+                .query_row(&format!("SELECT {} FROM swaps WHERE id = ?1", SWAP_COLUMNS), params![swap_id], swap_row)
+                .optional(),
+        )?;
+
+        row.map(into_swap).transpose()
+    }
+
+    fn list(&self) -> PyResult<Vec<StoredSwap>> {
+        self.query_swaps(&format!("SELECT {} FROM swaps ORDER BY created_at", SWAP_COLUMNS), Vec::new())
+    }
+
+    fn by_status(&self, statuses: &[SwapStatus]) -> PyResult<Vec<StoredSwap>> {
+        if statuses.is_empty() {
+            return Ok(Vec::new());
+        }
+        let placeholders = vec!["?"; statuses.len()].join(", ");
+        self.query_swaps(
+            &format!("SELECT {} FROM swaps WHERE status IN ({}) ORDER BY created_at", SWAP_COLUMNS, placeholders),
+            statuses.iter().map(|status| status.as_str().to_string()).collect(),
+        )
+    }
+
+    fn update_status(&self, swap_id: &str, status: SwapStatus, transaction_id: Option<&str>, now: u64) -> PyResult<bool> {
+        let updated = handle_rust_error(
+            "could not update swap status",
+            self.conn.execute(
+                "UPDATE swaps SET status = ?1, updated_at = ?2 WHERE id = ?3",
+                params![status.as_str(), now, swap_id],
+            ),
+        )?;
+        if updated == 0 {
+            return Ok(false);
+        }
+
+        handle_rust_error(
+            "could not update swap status",
+            self.conn.execute(
+                "INSERT INTO status_history (swap_id, status, transaction_id, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![swap_id, status.as_str(), transaction_id, now],
+            ),
+        )?;
+        Ok(true)
+    }
+
+    fn set_transaction(&self, swap_id: &str, kind: TransactionKind, txid: &str, now: u64) -> PyResult<bool> {
+        let column = match kind {
+            TransactionKind::Funding => "funding_txid",
+            TransactionKind::Claim => "claim_txid",
+            TransactionKind::Refund => "refund_txid",
+        };
+        let updated = handle_rust_error(
+            "could not update swap transaction",
+            self.conn.execute(
+                &format!("UPDATE swaps SET {} = ?1, updated_at = ?2 WHERE id = ?3", column),
+                params![txid, now, swap_id],
+            ),
+        )?;
+
+        Ok(updated > 0)
+    }
+
+    fn status_history(&self, swap_id: &str) -> PyResult<Vec<SwapStatusEntry>> {
+        let mut stmt = handle_rust_error(
+            COULD_NOT_QUERY,
+            self.conn.prepare(
+                "SELECT status, transaction_id, created_at FROM status_history WHERE swap_id = ?1 ORDER BY rowid",
+            ),
+        )?;
+        let rows = handle_rust_error(
+            COULD_NOT_QUERY,
+            stmt.query_map(params![swap_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, u64>(2)?))
+            })
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>()),
+        )?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(status, transaction_id, created_at)| SwapStatusEntry {
+                status: SwapStatus::from(status.as_str()),
+                transaction_id,
+                created_at,
+            })
+            .collect())
+    }
+
+    fn delete(&self, swap_id: &str) -> PyResult<bool> {
+        handle_rust_error(
+            "could not delete swap",
+            self.conn
+                .execute("DELETE FROM status_history WHERE swap_id = ?1", params![swap_id]),
+        )?;
+        let deleted = handle_rust_error(
+            "could not delete swap",
+            self.conn.execute("DELETE FROM swaps WHERE id = ?1", params![swap_id]),
+        )?;
+
+        Ok(deleted > 0)
+    }
+}
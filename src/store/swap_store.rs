@@ -0,0 +1,317 @@
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use pyo3::prelude::PyDictMethods;
+use pyo3::types::PyDict;
+use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
+
+use crate::store::sqlite::SqliteStorage;
+use crate::swaps::rescue::RescueSwap;
+use crate::types::status::SwapStatus;
+use crate::utils::errors::{handle_rust_error, new_python_error, BoltzError};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum TransactionKind {
+    Funding,
+    Claim,
+    Refund,
+}
+
+/// Storage backend of the SwapStore; implementations only persist rows,
+/// the queries on top of them live in SharedStore.
+pub(crate) trait SwapStorage: Send {
+    fn insert(&self, swap: &StoredSwap) -> PyResult<()>;
+    fn get(&self, swap_id: &str) -> PyResult<Option<StoredSwap>>;
+    fn list(&self) -> PyResult<Vec<StoredSwap>>;
+    fn by_status(&self, statuses: &[SwapStatus]) -> PyResult<Vec<StoredSwap>>;
+    fn update_status(&self, swap_id: &str, status: SwapStatus, transaction_id: Option<&str>, now: u64) -> PyResult<bool>;
+    fn set_transaction(&self, swap_id: &str, kind: TransactionKind, txid: &str, now: u64) -> PyResult<bool>;
+    fn status_history(&self, swap_id: &str) -> PyResult<Vec<SwapStatusEntry>>;
+    fn delete(&self, swap_id: &str) -> PyResult<bool>;
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn swap_not_found(swap_id: &str) -> pyo3::PyErr {
+    new_python_error::<BoltzError>(&format!("swap not found: {}", swap_id))
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct StoredSwap {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub swap_type: String,
+    #[pyo3(get)]
+    pub asset_send: String,
+    #[pyo3(get)]
+    pub asset_receive: String,
+    #[pyo3(get)]
+    pub status: Option<SwapStatus>,
+    #[pyo3(get)]
+    pub timeout_block_height: Option<u32>,
+    #[pyo3(get)]
+    pub lockup_address: Option<String>,
+    #[pyo3(get)]
+    pub funding_txid: Option<String>,
+    #[pyo3(get)]
+    pub claim_txid: Option<String>,
+    #[pyo3(get)]
+    pub refund_txid: Option<String>,
+    #[pyo3(get)]
+    pub created_at: u64,
+    #[pyo3(get)]
+    pub updated_at: u64,
+    pub(crate) data: String,
+}
+
+impl StoredSwap {
+    fn from_rescue(swap: &RescueSwap, status: Option<SwapStatus>) -> PyResult<Self> {
+        let created_at = now();
+
+        Ok(StoredSwap {
+            id: swap.id()?,
+            swap_type: swap.swap_type()?,
+            asset_send: swap.asset_send()?,
+            asset_receive: swap.asset_receive()?,
+            status,
+            timeout_block_height: swap.timeout_block_height(),
+            lockup_address: swap.lockup_address(),
+            funding_txid: None,
+            claim_txid: None,
+            refund_txid: None,
+            created_at,
+            updated_at: created_at,
+            data: handle_rust_error("could not serialize swap", serde_json::to_string(swap.to_value()))?,
+        })
+    }
+}
+
+#[pymethods]
+impl StoredSwap {
+    /// Keys, preimage and the response of Boltz the swap was stored with.
+    pub fn rescue_swap(&self) -> PyResult<RescueSwap> {
+        let data = handle_rust_error("could not parse stored swap", serde_json::from_str(self.data.as_str()))?;
+        RescueSwap::from_value(data)
+    }
+
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("id", self.id.clone())?;
+        dict.set_item("swap_type", self.swap_type.clone())?;
+        dict.set_item("asset_send", self.asset_send.clone())?;
+        dict.set_item("asset_receive", self.asset_receive.clone())?;
+        dict.set_item("status", self.status.map(|status| status.as_str()))?;
+        dict.set_item("timeout_block_height", self.timeout_block_height)?;
+        dict.set_item("lockup_address", self.lockup_address.clone())?;
+        dict.set_item("funding_txid", self.funding_txid.clone())?;
+        dict.set_item("claim_txid", self.claim_txid.clone())?;
+        dict.set_item("refund_txid", self.refund_txid.clone())?;
+        dict.set_item("created_at", self.created_at)?;
+        dict.set_item("updated_at", self.updated_at)?;
+        Ok(dict)
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct SwapStatusEntry {
+    #[pyo3(get)]
+    pub status: SwapStatus,
+    #[pyo3(get)]
+    pub transaction_id: Option<String>,
+    #[pyo3(get)]
+    pub created_at: u64,
+}
+
+#[pymethods]
+impl SwapStatusEntry {
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("status", self.status.as_str())?;
+        dict.set_item("transaction_id", self.transaction_id.clone())?;
+        dict.set_item("created_at", self.created_at)?;
+        Ok(dict)
+    }
+}
+
+// Handle on the storage that can be shared with background threads
+#[derive(Clone)]
+pub(crate) struct SharedStore(Arc<Mutex<Box<dyn SwapStorage>>>);
+
+impl SharedStore {
+    fn with<T>(&self, f: impl FnOnce(&dyn SwapStorage) -> PyResult<T>) -> PyResult<T> {
+        let storage = handle_rust_error("could not lock swap store", self.0.lock())?;
+        f(storage.as_ref())
+    }
+
+    pub fn add(&self, swap: &RescueSwap, status: Option<SwapStatus>) -> PyResult<StoredSwap> {
+        let stored = StoredSwap::from_rescue(swap, status)?;
+        self.with(|storage| {
+            storage.insert(&stored)?;
+            if let Some(status) = status {
+                storage.update_status(&stored.id, status, None, stored.created_at)?;
+            }
+            Ok(())
+        })?;
+
+        Ok(stored)
+    }
+
+    pub fn get(&self, swap_id: &str) -> PyResult<StoredSwap> {
+        self.with(|storage| storage.get(swap_id))?.ok_or_else(|| swap_not_found(swap_id))
+    }
+
+    pub fn all(&self) -> PyResult<Vec<StoredSwap>> {
+        self.with(|storage| storage.list())
+    }
+
+    pub fn by_status(&self, statuses: &[SwapStatus]) -> PyResult<Vec<StoredSwap>> {
+        self.with(|storage| storage.by_status(statuses))
+    }
+
+    // The lockup transaction is recorded as funding transaction when it is
+    // part of the status update
+    pub fn update_status(&self, swap_id: &str, status: SwapStatus, transaction_id: Option<&str>) -> PyResult<StoredSwap> {
+        let now = now();
+        self.with(|storage| {
+            if !storage.update_status(swap_id, status, transaction_id, now)? {
+                return Err(swap_not_found(swap_id));
+            }
+            if let (SwapStatus::TransactionMempool | SwapStatus::TransactionConfirmed, Some(txid)) = (status, transaction_id) {
+                storage.set_transaction(swap_id, TransactionKind::Funding, txid, now)?;
+            }
+            Ok(())
+        })?;
+
+        self.get(swap_id)
+    }
+
+    pub fn set_transaction(&self, swap_id: &str, kind: TransactionKind, txid: &str) -> PyResult<StoredSwap> {
+        if !self.with(|storage| storage.set_transaction(swap_id, kind, txid, now()))? {
+            return Err(swap_not_found(swap_id));
+        }
+        self.get(swap_id)
+    }
+
+    pub fn status_history(&self, swap_id: &str) -> PyResult<Vec<SwapStatusEntry>> {
+        self.with(|storage| storage.status_history(swap_id))
+    }
+
+    pub fn delete(&self, swap_id: &str) -> PyResult<bool> {
+        self.with(|storage| storage.delete(swap_id))
+    }
+
+    pub fn pending(&self) -> PyResult<Vec<StoredSwap>> {
+        Ok(self
+            .all()?
+            .into_iter()
+            .filter(|swap| !swap.status.is_some_and(|status| status.is_final()))
+            .collect())
+    }
+
+    pub fn needing_refund(&self, current_height: Option<u32>) -> PyResult<Vec<StoredSwap>> {
+        self.needing_refund_at(|_| current_height)
+    }
+
+    // Funded submarine and chain swaps that were neither claimed nor refunded
+    // and either failed or, if the height of the chain they were locked up on
+    // is known, timed out
+    pub fn needing_refund_at(&self, height: impl Fn(&str) -> Option<u32>) -> PyResult<Vec<StoredSwap>> {
+        Ok(self
+            .all()?
+            .into_iter()
+            .filter(|swap| swap.swap_type != "reverse" && swap.funding_txid.is_some())
+            .filter(|swap| swap.claim_txid.is_none() && swap.refund_txid.is_none())
+            .filter(|swap| match swap.status {
+                Some(status) if status.is_final() => status.requires_refund(),
+                _ => matches!(
+                    (height(swap.asset_send.as_str()), swap.timeout_block_height),
+                    (Some(height), Some(timeout)) if height >= timeout
+                ),
+            })
+            .collect())
+    }
+}
+
+#[pyclass]
+pub struct SwapStore {
+    store: SharedStore,
+}
+
+impl SwapStore {
+    pub(crate) fn shared(&self) -> SharedStore {
+        self.store.clone()
+    }
+}
+
+#[pymethods]
+impl SwapStore {
+    /// Opens, or creates, the SQLite database at path; ":memory:" keeps the
+    /// swaps in memory only.
+    #[new]
+    pub fn new(path: String) -> PyResult<Self> {
+        let storage: Box<dyn SwapStorage> = Box::new(SqliteStorage::open(path.as_str())?);
+
+        Ok(SwapStore {
+            store: SharedStore(Arc::new(Mutex::new(storage))),
+        })
+    }
+
+    #[pyo3(signature = (swap, status=None))]
+    pub fn add(&self, swap: RescueSwap, status: Option<SwapStatus>) -> PyResult<StoredSwap> {
+        self.store.add(&swap, status)
+    }
+
+    pub fn get(&self, swap_id: String) -> PyResult<StoredSwap> {
+        self.store.get(swap_id.as_str())
+    }
+
+    pub fn all_swaps(&self) -> PyResult<Vec<StoredSwap>> {
+        self.store.all()
+    }
+
+    pub fn pending_swaps(&self) -> PyResult<Vec<StoredSwap>> {
+        self.store.pending()
+    }
+
+    #[pyo3(signature = (current_height=None))]
+    pub fn swaps_needing_refund(&self, current_height: Option<u32>) -> PyResult<Vec<StoredSwap>> {
+        self.store.needing_refund(current_height)
+    }
+
+    pub fn swaps_by_status(&self, statuses: Vec<SwapStatus>) -> PyResult<Vec<StoredSwap>> {
+        self.store.by_status(&statuses)
+    }
+
+    #[pyo3(signature = (swap_id, status, transaction_id=None))]
+    pub fn update_status(&self, swap_id: String, status: SwapStatus, transaction_id: Option<String>) -> PyResult<StoredSwap> {
+        self.store.update_status(swap_id.as_str(), status, transaction_id.as_deref())
+    }
+
+    pub fn set_funding_txid(&self, swap_id: String, txid: String) -> PyResult<StoredSwap> {
+        self.store.set_transaction(swap_id.as_str(), TransactionKind::Funding, txid.as_str())
+    }
+
+    pub fn set_claim_txid(&self, swap_id: String, txid: String) -> PyResult<StoredSwap> {
+        self.store.set_transaction(swap_id.as_str(), TransactionKind::Claim, txid.as_str())
+    }
+
+    pub fn set_refund_txid(&self, swap_id: String, txid: String) -> PyResult<StoredSwap> {
+        self.store.set_transaction(swap_id.as_str(), TransactionKind::Refund, txid.as_str())
+    }
+
+    pub fn status_history(&self, swap_id: String) -> PyResult<Vec<SwapStatusEntry>> {
+        self.store.status_history(swap_id.as_str())
+    }
+
+    pub fn delete(&self, swap_id: String) -> PyResult<bool> {
+        self.store.delete(swap_id.as_str())
+    }
+}
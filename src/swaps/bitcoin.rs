@@ -1,13 +1,40 @@
-use boltz_client::boltz::SwapType;
-use pyo3::{pyclass, pymethods, PyResult};
+use std::str::FromStr;
 
+use bitcoin::absolute::LockTime;
+use bitcoin::consensus::encode::{deserialize, serialize_hex};
+use bitcoin::hashes::{hash160, ripemd160, sha256, Hash};
+use bitcoin::secp256k1::Keypair;
+use bitcoin::sighash::{Prevouts, SighashCache};
+use bitcoin::{Address, Amount, Denomination, OutPoint, ScriptBuf, TapSighashType, Transaction, TxOut, Witness};
+use boltz_client::boltz::{Side, SwapTxKind, SwapType};
+use boltz_client::util::secrets::Preimage;
+use boltz_client::Bolt11Invoice;
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, Py, PyErr, PyRef, PyResult, Python};
+
+use crate::client::boltz::{BoltzApi, Client};
+use crate::client::backend::Backend;
+use crate::swaps::liquid::parse_side;
+use crate::swaps::musig::{partial_sign, KeyPathSigner};
+use crate::types::chain::CreateChainResponse;
+use crate::types::client::SubmarinePair;
+use crate::types::onchain::Utxo;
+use crate::types::reverse::CreateReverseResponse;
 use crate::types::submarine::CreateSubmarineResponse;
-use crate::utils::errors::handle_rust_error;
-use crate::utils::keys::parse_public_key;
+use crate::types::transaction::LockupTx;
+use crate::utils::errors::{
+    handle_boltz_error, handle_rust_error, handle_rust_error_as, new_python_error, BoltzError,
+    InvalidAddressError, InvalidInvoiceError, ScriptError, SigningError,
+};
+use crate::utils::keys::{parse_keypair, parse_public_key};
+use crate::utils::preimage::PreimageArg;
+use crate::utils::network::{parse_bitcoin_network, parse_chain};
 
 #[pyclass]
+#[derive(Clone)]
 pub struct BtcSwapScript {
-    script: boltz_client::BtcSwapScript,
+    pub(crate) script: boltz_client::BtcSwapScript,
+    pub(crate) swap_id: String,
 }
 
 #[pymethods]
@@ -17,18 +44,489 @@ impl BtcSwapScript {
         created_response: CreateSubmarineResponse,
         our_pubkey: Vec<u8>,
     ) -> PyResult<Self> {
+        let swap_id = created_response.id.clone();
         Ok(BtcSwapScript {
-            script: handle_rust_error(
+            script: handle_boltz_error::<ScriptError, _>(
                 "could not parse response",
                 boltz_client::BtcSwapScript::submarine_from_swap_resp(
                     &created_response.try_into()?,
                     parse_public_key(our_pubkey)?,
                 ),
             )?,
+            swap_id,
+        })
+    }
+
+    #[staticmethod]
+    fn from_reverse_response(
+        created_response: CreateReverseResponse,
+        our_pubkey: Vec<u8>,
+    ) -> PyResult<Self> {
+        let swap_id = created_response.id.clone();
+        Ok(BtcSwapScript {
+            script: handle_boltz_error::<ScriptError, _>(
+                "could not parse response",
+                boltz_client::BtcSwapScript::reverse_from_swap_resp(
+                    &created_response.try_into()?,
+                    parse_public_key(our_pubkey)?,
+                ),
+            )?,
+            swap_id,
+        })
+    }
+
+    #[staticmethod]
+    fn from_chain_response(
+        created_response: CreateChainResponse,
+        side: String,
+        our_pubkey: Vec<u8>,
+    ) -> PyResult<Self> {
+        let side = parse_side(side.as_str())?;
+        let details = match side {
+            Side::Lockup => created_response.lockup_details,
+            Side::Claim => created_response.claim_details,
+        };
+        Ok(BtcSwapScript {
+            script: handle_boltz_error::<ScriptError, _>(
+                "could not parse response",
+                boltz_client::BtcSwapScript::chain_from_swap_resp(
+                    side,
+                    details.try_into()?,
+                    parse_public_key(our_pubkey)?,
+                ),
+            )?,
+            swap_id: created_response.id,
         })
     }
 
     fn is_submarine(&self) -> bool {
         self.script.swap_type == SwapType::Submarine
     }
+
+    fn is_reverse(&self) -> bool {
+        self.script.swap_type == SwapType::ReverseSubmarine
+    }
+
+    fn is_chain(&self) -> bool {
+        self.script.swap_type == SwapType::Chain
+    }
+
+    fn lockup_address(&self, network: String) -> PyResult<String> {
+        let chain = parse_chain("BTC", network.as_str())?;
+        let address = handle_boltz_error::<ScriptError, _>("could not compute lockup address", self.script.to_address(chain))?;
+
+        Ok(address.to_string())
+    }
+
+    #[pyo3(signature = (
+        response,
+        invoice,
+        network,
+        current_height,
+        pair=None,
+        min_timeout_delta=2,
+        max_timeout_delta=2016,
+        max_fee=None,
+    ))]
+    fn verify(
+        &self,
+        response: CreateSubmarineResponse,
+        invoice: String,
+        network: String,
+        current_height: u64,
+        pair: Option<SubmarinePair>,
+        min_timeout_delta: u64,
+        max_timeout_delta: u64,
+        max_fee: Option<u64>,
+    ) -> PyResult<()> {
+        let chain = parse_chain("BTC", network.as_str())?;
+        let address = response.address.clone();
+        let bip21 = response.bip21.clone();
+        let expected_amount = response.expected_amount;
+        let timeout_block_height = response.timeout_block_height;
+
+        let rebuilt = handle_boltz_error::<ScriptError, _>(
+            "could not parse response",
+            boltz_client::BtcSwapScript::submarine_from_swap_resp(
+                &response.clone().try_into()?,
+                self.script.sender_pubkey,
+            ),
+        )?;
+        if rebuilt.receiver_pubkey != parse_public_key(response.claim_public_key)? {
+            return Err(new_python_error::<ScriptError>("claim public key does not match swap tree"));
+        }
+        let rebuilt_address = handle_boltz_error::<ScriptError, _>("could not compute lockup address", rebuilt.to_address(chain))?;
+        if rebuilt_address.to_string() != address {
+            return Err(new_python_error::<ScriptError>("address does not match swap tree"));
+        }
+
+        let (bip21_address, bip21_amount) = parse_bip21(bip21.as_str())?;
+        if bip21_address != address {
+            return Err(new_python_error::<InvalidAddressError>("BIP21 address does not match address"));
+        }
+        if let Some(bip21_amount) = bip21_amount {
+            if bip21_amount != expected_amount {
+                return Err(new_python_error::<BoltzError>("BIP21 amount does not match expected amount"));
+            }
+        }
+
+        let invoice = handle_rust_error_as::<InvalidInvoiceError, _, _>("could not parse invoice", Bolt11Invoice::from_str(invoice.as_str()))?;
+        let payment_hash = ripemd160::Hash::hash(invoice.payment_hash().as_byte_array());
+        if payment_hash.as_byte_array() != rebuilt.hashlock.as_byte_array() {
+            return Err(new_python_error::<ScriptError>("hashlock does not match invoice payment hash"));
+        }
+
+        let invoice_amount = invoice
+            .amount_milli_satoshis()
+            .ok_or_else(|| new_python_error::<InvalidInvoiceError>("invoice has no amount"))?
+            .div_ceil(1000);
+        let pair_fee = pair.map(|pair| {
+            let percentage_fee = (invoice_amount as f64 * pair.fees.percentage / 100.0).ceil() as u64;
+            percentage_fee.saturating_add(pair.fees.miner_fees)
+        });
+        let fee = match (pair_fee, max_fee) {
+            (Some(pair_fee), Some(max_fee)) => pair_fee.min(max_fee),
+            (Some(fee), None) | (None, Some(fee)) => fee,
+            (None, None) => return Err(PyValueError::new_err("either pair or max_fee has to be set")),
+        };
+        let max_amount = invoice_amount.saturating_add(fee);
+        if expected_amount < invoice_amount || expected_amount > max_amount {
+            return Err(new_python_error::<BoltzError>(&format!(
+                "expected amount {} is not within {} and {}",
+                expected_amount, invoice_amount, max_amount
+            )));
+        }
+
+        let timeout_bounds = current_height
+            .checked_add(min_timeout_delta)
+            .zip(current_height.checked_add(max_timeout_delta));
+        let (min_timeout, max_timeout) =
+            timeout_bounds.ok_or_else(|| PyValueError::new_err("timeout delta overflows the current height"))?;
+        if timeout_block_height < min_timeout || timeout_block_height > max_timeout {
+            return Err(new_python_error::<BoltzError>(&format!(
+                "timeout block height {} is not within {} and {} blocks of {}",
+                timeout_block_height, min_timeout_delta, max_timeout_delta, current_height
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn script_pubkey(&self) -> PyResult<Vec<u8>> {
+        Ok(self.lockup_script()?.to_bytes())
+    }
+
+    fn fetch_utxo(&self, py: Python, backend: Backend) -> PyResult<Option<Utxo>> {
+        let backend = backend.get();
+        if backend.is_liquid() {
+            return Err(PyValueError::new_err("backend is not connected to a Bitcoin server"));
+        }
+
+        let script_pubkey = self.lockup_script()?;
+        let utxos = py.allow_threads(|| backend.utxos(script_pubkey.as_bytes()))?;
+
+        Ok(utxos.into_iter().next())
+    }
+
+    pub(crate) fn sign_submarine_claim(&self, py: Python, client: PyRef<Client>, keys: Vec<u8>, invoice: String) -> PyResult<()> {
+        if self.script.swap_type != SwapType::Submarine {
+            return Err(PyValueError::new_err("not a submarine swap"));
+        }
+
+        let api = client.boltz();
+        let swap_id = self.swap_id.clone();
+        let details = py.allow_threads(|| api.get_submarine_claim_details(swap_id))?;
+
+        let preimage = handle_rust_error("could not decode preimage", hex::decode(&details.preimage))?;
+        let invoice = handle_rust_error_as::<InvalidInvoiceError, _, _>("could not parse invoice", Bolt11Invoice::from_str(invoice.as_str()))?;
+        if sha256::Hash::hash(preimage.as_slice()) != *invoice.payment_hash() {
+            return Err(new_python_error::<InvalidInvoiceError>("preimage does not match invoice payment hash"));
+        }
+        if hash160::Hash::hash(preimage.as_slice()) != self.script.hashlock {
+            return Err(new_python_error::<ScriptError>("preimage does not match swap script hashlock"));
+        }
+
+        let spend_info = handle_boltz_error::<ScriptError, _>(
+            "could not compute taproot spend info",
+            self.script.taproot_spendinfo(),
+        )?;
+        let (partial_sig, pub_nonce) = partial_sign(
+            self.script.musig_keyagg_cache(),
+            spend_info.tap_tweak().as_byte_array(),
+            &parse_keypair(keys)?,
+            details.pub_nonce.as_str(),
+            details.transaction_hash.as_str(),
+        )?;
+
+        py.allow_threads(|| api.post_submarine_claim_signature(&self.swap_id, pub_nonce, partial_sig))
+    }
+}
+
+impl BtcSwapScript {
+    pub(crate) fn lockup_script(&self) -> PyResult<ScriptBuf> {
+        let spend_info = handle_boltz_error::<ScriptError, _>(
+            "could not compute taproot spend info",
+            self.script.taproot_spendinfo(),
+        )?;
+
+        Ok(ScriptBuf::new_p2tr_tweaked(spend_info.output_key()))
+    }
+
+    pub(crate) fn find_lockup_utxo(&self, lockup_tx: &Transaction) -> PyResult<(OutPoint, TxOut)> {
+        let script_pubkey = self.lockup_script()?;
+
+        lockup_tx
+            .output
+            .iter()
+            .enumerate()
+            .find(|(_, out)| out.script_pubkey == script_pubkey)
+            .map(|(vout, out)| (OutPoint::new(lockup_tx.compute_txid(), vout as u32), out.clone()))
+            .ok_or_else(|| new_python_error::<ScriptError>("lockup transaction does not pay to swap script"))
+    }
+}
+
+fn parse_bip21(bip21: &str) -> PyResult<(String, Option<u64>)> {
+    let uri = bip21
+        .strip_prefix("bitcoin:")
+        .ok_or_else(|| new_python_error::<InvalidAddressError>("invalid BIP21 scheme"))?;
+    let (address, query) = uri.split_once('?').unwrap_or((uri, ""));
+
+    let mut amount = None;
+    for param in query.split('&') {
+        if let Some(value) = param.strip_prefix("amount=") {
+            let value = handle_rust_error(
+                "could not parse BIP21 amount",
+                Amount::from_str_in(value, Denomination::Bitcoin),
+            )?;
+            amount = Some(value.to_sat());
+        }
+    }
+
+    Ok((address.to_string(), amount))
+}
+
+pub(crate) fn parse_transaction(tx_hex: String) -> Result<Transaction, PyErr> {
+    let bytes = handle_rust_error("could not decode transaction hex", hex::decode(tx_hex))?;
+    handle_rust_error("could not parse transaction", deserialize(bytes.as_slice()))
+}
+
+fn lockup_transaction(py: Python, script: &BtcSwapScript, lockup_tx: Option<LockupTx>, backend: Option<Backend>) -> PyResult<Transaction> {
+    match (lockup_tx, backend) {
+        (Some(lockup_tx), _) => parse_transaction(lockup_tx.into_hex()),
+        (None, Some(backend)) => {
+            let backend = backend.get();
+            if backend.is_liquid() {
+                return Err(PyValueError::new_err("backend is not connected to a Bitcoin server"));
+            }
+
+            let script_pubkey = script.lockup_script()?;
+            let tx = py.allow_threads(|| backend.lockup_transaction(script_pubkey.as_bytes()))?;
+            handle_rust_error("could not parse transaction", deserialize(tx.as_slice()))
+        }
+        (None, None) => Err(PyValueError::new_err("either lockup_tx or backend is required")),
+    }
+}
+
+#[pyclass]
+pub struct BtcSwapTx {
+    tx: boltz_client::BtcSwapTx,
+    swap_id: String,
+    client: Option<Py<Client>>,
+}
+
+#[pymethods]
+impl BtcSwapTx {
+    #[staticmethod]
+    #[pyo3(signature = (script, output_address, lockup_tx, network, client=None, backend=None))]
+    pub(crate) fn new_claim(
+        py: Python,
+        script: BtcSwapScript,
+        output_address: String,
+        lockup_tx: Option<LockupTx>,
+        network: String,
+        client: Option<Py<Client>>,
+        backend: Option<Backend>,
+    ) -> PyResult<Self> {
+        match (&script.script.swap_type, &script.script.side) {
+            (SwapType::ReverseSubmarine, _) | (SwapType::Chain, Some(Side::Claim)) => {}
+            _ => return Err(PyValueError::new_err("claims are only supported for reverse and chain swaps")),
+        }
+        Self::new(py, SwapTxKind::Claim, script, output_address, lockup_tx, network, client, backend)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (script, output_address, lockup_tx, network, client=None, backend=None))]
+    pub(crate) fn new_refund(
+        py: Python,
+        script: BtcSwapScript,
+        output_address: String,
+        lockup_tx: Option<LockupTx>,
+        network: String,
+        client: Option<Py<Client>>,
+        backend: Option<Backend>,
+    ) -> PyResult<Self> {
+        match (&script.script.swap_type, &script.script.side) {
+            (SwapType::Submarine, _) | (SwapType::Chain, Some(Side::Lockup)) => {}
+            _ => return Err(PyValueError::new_err("refunds are only supported for submarine and chain swaps")),
+        }
+        Self::new(py, SwapTxKind::Refund, script, output_address, lockup_tx, network, client, backend)
+    }
+
+    /// Signs the cooperative key-path claim with a partial signature of Boltz
+    /// when there is a client. The hashlock leaf can be spent right away, so
+    /// when Boltz does not cooperate the script-path claim is signed instead,
+    /// unless fallback is false.
+    #[pyo3(signature = (keys, preimage, fee, cooperative=true, fallback=true))]
+    pub(crate) fn sign_claim(
+        &self,
+        py: Python,
+        keys: Vec<u8>,
+        preimage: PreimageArg,
+        fee: u64,
+        cooperative: bool,
+        fallback: bool,
+    ) -> PyResult<String> {
+        let keys = parse_keypair(keys)?;
+        let preimage_hex = preimage.into_hex();
+        let preimage = handle_boltz_error::<BoltzError, _>("could not parse preimage", Preimage::from_str(preimage_hex.as_str()))?;
+
+        let tx = handle_boltz_error::<SigningError, _>(
+            "could not sign claim transaction",
+            self.tx.sign_claim(&keys, &preimage, fee, None),
+        )?;
+
+        let tx = match (&self.client, cooperative) {
+            (Some(client), true) => {
+                let client = client.borrow(py);
+                let api = client.boltz();
+                match py.allow_threads(|| self.cooperative_spend(api, &keys, tx.clone(), Some(preimage_hex))) {
+                    Ok(tx) => tx,
+                    Err(_) if fallback => tx,
+                    Err(err) => return Err(err),
+                }
+            }
+            _ => tx,
+        };
+
+        Ok(serialize_hex(&tx))
+    }
+
+    /// Signs the cooperative key-path refund with a partial signature of Boltz
+    /// when there is a client and raises when Boltz does not cooperate.
+    /// Otherwise the script-path refund is signed, which is only valid after
+    /// the timeout.
+    #[pyo3(signature = (keys, fee, cooperative=true))]
+    pub(crate) fn sign_refund(&self, py: Python, keys: Vec<u8>, fee: u64, cooperative: bool) -> PyResult<String> {
+        Ok(self.refund(py, keys, fee, cooperative, false)?.0)
+    }
+}
+
+impl BtcSwapTx {
+    // Signed refund and whether it is cooperative; the script path is only
+    // used without a client, or when the swap expired and Boltz did not
+    // cooperate
+    pub(crate) fn refund(&self, py: Python, keys: Vec<u8>, fee: u64, cooperative: bool, expired: bool) -> PyResult<(String, bool)> {
+        let keys = parse_keypair(keys)?;
+
+        if cooperative {
+            if let Some(client) = &self.client {
+                match self.cooperative_refund(py, client, &keys, fee) {
+                    Ok(tx) => return Ok((tx, true)),
+                    Err(_) if expired => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        let tx = handle_boltz_error::<SigningError, _>(
+            "could not sign refund transaction",
+            self.tx.sign_refund(&keys, fee, None),
+        )?;
+
+        Ok((serialize_hex(&tx), false))
+    }
+
+    fn cooperative_refund(&self, py: Python, client: &Py<Client>, keys: &Keypair, fee: u64) -> PyResult<String> {
+        let mut tx = handle_boltz_error::<SigningError, _>(
+            "could not sign refund transaction",
+            self.tx.sign_refund(keys, fee, None),
+        )?;
+        // the key path does not wait for the timeout
+        tx.lock_time = LockTime::ZERO;
+
+        let client = client.borrow(py);
+        let api = client.boltz();
+        let tx = py.allow_threads(|| self.cooperative_spend(api, keys, tx, None))?;
+
+        Ok(serialize_hex(&tx))
+    }
+
+    // Replaces the script-path witness of tx with the key-path signature
+    // aggregated with a partial signature of Boltz
+    fn cooperative_spend(&self, api: &BoltzApi, keys: &Keypair, mut tx: Transaction, preimage: Option<String>) -> PyResult<Transaction> {
+        tx.input[0].witness = Witness::new();
+        let sighash = handle_rust_error_as::<SigningError, _, _>(
+            "could not compute signature hash",
+            SighashCache::new(&tx).taproot_key_spend_signature_hash(
+                0,
+                &Prevouts::All(&[&self.tx.utxo.1]),
+                TapSighashType::Default,
+            ),
+        )?;
+        let spend_info = handle_boltz_error::<ScriptError, _>(
+            "could not compute taproot spend info",
+            self.tx.swap_script.taproot_spendinfo(),
+        )?;
+
+        let signer = KeyPathSigner::new(
+            self.tx.swap_script.musig_keyagg_cache(),
+            spend_info.tap_tweak().as_byte_array(),
+            keys,
+            sighash.to_byte_array(),
+        )?;
+        let res = api.get_partial_signature(
+            &self.tx.kind,
+            &self.tx.swap_script.swap_type,
+            &self.swap_id,
+            signer.pub_nonce(),
+            serialize_hex(&tx),
+            preimage,
+        )?;
+        let signature = signer.sign(keys, &res.pub_nonce, &res.partial_signature)?;
+        tx.input[0].witness = Witness::from_slice(&[signature]);
+
+        Ok(tx)
+    }
+
+    fn new(
+        py: Python,
+        kind: SwapTxKind,
+        script: BtcSwapScript,
+        output_address: String,
+        lockup_tx: Option<LockupTx>,
+        network: String,
+        client: Option<Py<Client>>,
+        backend: Option<Backend>,
+    ) -> PyResult<Self> {
+        let output_address = handle_rust_error_as::<InvalidAddressError, _, _>(
+            "could not parse output address",
+            Address::from_str(output_address.as_str()),
+        )?;
+        let output_address = handle_rust_error_as::<InvalidAddressError, _, _>(
+            "output address is not valid for network",
+            output_address.require_network(parse_bitcoin_network(network.as_str())?),
+        )?;
+        let utxo = script.find_lockup_utxo(&lockup_transaction(py, &script, lockup_tx, backend)?)?;
+
+        Ok(BtcSwapTx {
+            tx: boltz_client::BtcSwapTx {
+                kind,
+                swap_script: script.script,
+                output_address,
+                utxo,
+            },
+            swap_id: script.swap_id,
+            client,
+        })
+    }
 }
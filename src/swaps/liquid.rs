@@ -0,0 +1,419 @@
+use std::str::FromStr;
+
+use bitcoin::secp256k1::Keypair;
+use boltz_client::boltz::{Side, SwapTxKind, SwapType};
+use boltz_client::elements::encode::{deserialize, serialize_hex};
+use boltz_client::elements::hashes::Hash;
+use boltz_client::elements::secp256k1_zkp::Secp256k1;
+use boltz_client::elements::sighash::{Prevouts, SighashCache};
+use boltz_client::elements::{Address, BlockHash, LockTime, OutPoint, SchnorrSighashType, Script, Transaction, TxOut};
+use boltz_client::network::Chain;
+use boltz_client::util::secrets::Preimage;
+use boltz_client::Amount;
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, Py, PyErr, PyResult, Python};
+
+use crate::client::backend::Backend;
+use crate::client::boltz::{BoltzApi, Client};
+use crate::swaps::musig::KeyPathSigner;
+use crate::types::chain::CreateChainResponse;
+use crate::types::reverse::CreateReverseResponse;
+use crate::types::submarine::CreateSubmarineResponse;
+use crate::types::transaction::LockupTx;
+use crate::utils::errors::{
+    handle_boltz_error, handle_rust_error, handle_rust_error_as, new_python_error, BoltzError,
+    InvalidAddressError, ScriptError, SigningError,
+};
+use crate::utils::keys::{parse_keypair, parse_public_key};
+use crate::utils::preimage::PreimageArg;
+use crate::utils::network::{parse_chain, parse_liquid_address_params};
+
+const COULD_NOT_PARSE_RESPONSE: &str = "could not parse response";
+
+const LIQUID_GENESIS_HASH: &str = "1466275836220db2944ca059a3a10ef6fd2ea684b0688d2c379296888a206003";
+const LIQUID_TESTNET_GENESIS_HASH: &str = "a771da8e52ee6ad581ed1e9a99825e5b3b7992225534eaa2ae23244fe26ab1c1";
+const LIQUID_REGTEST_GENESIS_HASH: &str = "00902a6b70c2ca83b5d9c815d96a0e2f4202179316970d14ea1847dae5b1ca21";
+
+#[pyclass]
+#[derive(Clone)]
+pub struct LBtcSwapScript {
+    pub(crate) script: boltz_client::LBtcSwapScript,
+    pub(crate) swap_id: String,
+}
+
+#[pymethods]
+impl LBtcSwapScript {
+    #[staticmethod]
+    fn from_submarine_response(
+        created_response: CreateSubmarineResponse,
+        our_pubkey: Vec<u8>,
+    ) -> PyResult<Self> {
+        let swap_id = created_response.id.clone();
+        Ok(LBtcSwapScript {
+            script: handle_boltz_error::<ScriptError, _>(
+                COULD_NOT_PARSE_RESPONSE,
+                boltz_client::LBtcSwapScript::submarine_from_swap_resp(
+                    &created_response.try_into()?,
+                    parse_public_key(our_pubkey)?,
+                ),
+            )?,
+            swap_id,
+        })
+    }
+
+    #[staticmethod]
+    fn from_reverse_response(
+        created_response: CreateReverseResponse,
+        our_pubkey: Vec<u8>,
+    ) -> PyResult<Self> {
+        let swap_id = created_response.id.clone();
+        Ok(LBtcSwapScript {
+            script: handle_boltz_error::<ScriptError, _>(
+                COULD_NOT_PARSE_RESPONSE,
+                boltz_client::LBtcSwapScript::reverse_from_swap_resp(
+                    &created_response.try_into()?,
+                    parse_public_key(our_pubkey)?,
+                ),
+            )?,
+            swap_id,
+        })
+    }
+
+    #[staticmethod]
+    fn from_chain_response(
+        created_response: CreateChainResponse,
+        side: String,
+        our_pubkey: Vec<u8>,
+    ) -> PyResult<Self> {
+        let side = parse_side(side.as_str())?;
+        let details = match side {
+            Side::Lockup => created_response.lockup_details,
+            Side::Claim => created_response.claim_details,
+        };
+        Ok(LBtcSwapScript {
+            script: handle_boltz_error::<ScriptError, _>(
+                COULD_NOT_PARSE_RESPONSE,
+                boltz_client::LBtcSwapScript::chain_from_swap_resp(
+                    side,
+                    details.try_into()?,
+                    parse_public_key(our_pubkey)?,
+                ),
+            )?,
+            swap_id: created_response.id,
+        })
+    }
+
+    fn is_submarine(&self) -> bool {
+        self.script.swap_type == SwapType::Submarine
+    }
+
+    fn is_reverse(&self) -> bool {
+        self.script.swap_type == SwapType::ReverseSubmarine
+    }
+
+    fn is_chain(&self) -> bool {
+        self.script.swap_type == SwapType::Chain
+    }
+
+    fn blinding_key(&self) -> Vec<u8> {
+        self.script.blinding_key.secret_key().as_ref().to_vec()
+    }
+
+    fn lockup_address(&self, network: String) -> PyResult<String> {
+        let chain = parse_liquid_chain(network.as_str())?;
+        let address = handle_boltz_error::<ScriptError, _>("could not compute lockup address", self.script.to_address(chain))?;
+
+        Ok(address.to_string())
+    }
+
+    fn validate_address(&self, network: String, address: String) -> PyResult<bool> {
+        let chain = parse_liquid_chain(network.as_str())?;
+
+        Ok(self.script.validate_address(chain, address).is_ok())
+    }
+}
+
+impl LBtcSwapScript {
+    pub(crate) fn lockup_script(&self, chain: Chain) -> PyResult<Script> {
+        let address = handle_boltz_error::<ScriptError, _>("could not compute lockup address", self.script.to_address(chain))?;
+
+        Ok(address.script_pubkey())
+    }
+
+    pub(crate) fn find_lockup_utxo(&self, chain: Chain, lockup_tx: &Transaction) -> PyResult<(OutPoint, TxOut)> {
+        let script_pubkey = self.lockup_script(chain)?;
+
+        lockup_tx
+            .output
+            .iter()
+            .enumerate()
+            .find(|(_, out)| out.script_pubkey == script_pubkey)
+            .map(|(vout, out)| (OutPoint::new(lockup_tx.txid(), vout as u32), out.clone()))
+            .ok_or_else(|| new_python_error::<ScriptError>("lockup transaction does not pay to swap script"))
+    }
+}
+
+pub(crate) fn parse_side(side: &str) -> Result<Side, PyErr> {
+    match side {
+        "lockup" => Ok(Side::Lockup),
+        "claim" => Ok(Side::Claim),
+        _ => Err(PyValueError::new_err("invalid side")),
+    }
+}
+
+fn parse_liquid_chain(network: &str) -> Result<Chain, PyErr> {
+    parse_chain("L-BTC", network)
+}
+
+pub(crate) fn parse_liquid_transaction(tx_hex: String) -> Result<Transaction, PyErr> {
+    let bytes = handle_rust_error("could not decode transaction hex", hex::decode(tx_hex))?;
+    handle_rust_error("could not parse transaction", deserialize(bytes.as_slice()))
+}
+
+fn genesis_hash(chain: Chain, genesis_hash: Option<String>) -> Result<BlockHash, PyErr> {
+    let genesis_hash = genesis_hash.unwrap_or_else(|| {
+        match chain {
+            Chain::Liquid => LIQUID_GENESIS_HASH,
+            Chain::LiquidTestnet => LIQUID_TESTNET_GENESIS_HASH,
+            _ => LIQUID_REGTEST_GENESIS_HASH,
+        }
+        .to_string()
+    });
+    handle_rust_error("could not parse genesis hash", BlockHash::from_str(genesis_hash.as_str()))
+}
+
+#[pyclass]
+pub struct LBtcSwapTx {
+    tx: boltz_client::LBtcSwapTx,
+    swap_id: String,
+    client: Option<Py<Client>>,
+}
+
+#[pymethods]
+impl LBtcSwapTx {
+    #[staticmethod]
+    #[pyo3(signature = (script, output_address, lockup_tx, network, client=None, genesis_hash=None, backend=None))]
+    pub(crate) fn new_claim(
+        py: Python,
+        script: LBtcSwapScript,
+        output_address: String,
+        lockup_tx: Option<LockupTx>,
+        network: String,
+        client: Option<Py<Client>>,
+        genesis_hash: Option<String>,
+        backend: Option<Backend>,
+    ) -> PyResult<Self> {
+        match (&script.script.swap_type, &script.script.side) {
+            (SwapType::ReverseSubmarine, _) | (SwapType::Chain, Some(Side::Claim)) => {}
+            _ => return Err(PyValueError::new_err("claims are only supported for reverse and chain swaps")),
+        }
+        Self::new(py, SwapTxKind::Claim, script, output_address, lockup_tx, network, client, genesis_hash, backend)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (script, output_address, lockup_tx, network, client=None, genesis_hash=None, backend=None))]
+    pub(crate) fn new_refund(
+        py: Python,
+        script: LBtcSwapScript,
+        output_address: String,
+        lockup_tx: Option<LockupTx>,
+        network: String,
+        client: Option<Py<Client>>,
+        genesis_hash: Option<String>,
+        backend: Option<Backend>,
+    ) -> PyResult<Self> {
+        match (&script.script.swap_type, &script.script.side) {
+            (SwapType::Submarine, _) | (SwapType::Chain, Some(Side::Lockup)) => {}
+            _ => return Err(PyValueError::new_err("refunds are only supported for submarine and chain swaps")),
+        }
+        Self::new(py, SwapTxKind::Refund, script, output_address, lockup_tx, network, client, genesis_hash, backend)
+    }
+
+    fn unblinded_amount(&self) -> PyResult<u64> {
+        let secrets = handle_rust_error_as::<ScriptError, _, _>(
+            "could not unblind lockup output",
+            self.tx
+                .funding_utxo
+                .unblind(&Secp256k1::new(), self.tx.swap_script.blinding_key.secret_key()),
+        )?;
+
+        Ok(secrets.value)
+    }
+
+    /// Signs the cooperative key-path claim with a partial signature of Boltz
+    /// when there is a client. The hashlock leaf can be spent right away, so
+    /// when Boltz does not cooperate the script-path claim is signed instead,
+    /// unless fallback is false.
+    #[pyo3(signature = (keys, preimage, fee, cooperative=true, fallback=true))]
+    pub(crate) fn sign_claim(
+        &self,
+        py: Python,
+        keys: Vec<u8>,
+        preimage: PreimageArg,
+        fee: u64,
+        cooperative: bool,
+        fallback: bool,
+    ) -> PyResult<String> {
+        let keys = parse_keypair(keys)?;
+        let preimage_hex = preimage.into_hex();
+        let preimage = handle_boltz_error::<BoltzError, _>("could not parse preimage", Preimage::from_str(preimage_hex.as_str()))?;
+
+        let tx = handle_boltz_error::<SigningError, _>(
+            "could not sign claim transaction",
+            self.tx.sign_claim(&keys, &preimage, Amount::from_sat(fee), None),
+        )?;
+
+        let tx = match (&self.client, cooperative) {
+            (Some(client), true) => {
+                let client = client.borrow(py);
+                let api = client.boltz();
+                match py.allow_threads(|| self.cooperative_spend(api, &keys, tx.clone(), Some(preimage_hex))) {
+                    Ok(tx) => tx,
+                    Err(_) if fallback => tx,
+                    Err(err) => return Err(err),
+                }
+            }
+            _ => tx,
+        };
+
+        Ok(serialize_hex(&tx))
+    }
+
+    /// Signs the cooperative key-path refund with a partial signature of Boltz
+    /// when there is a client and raises when Boltz does not cooperate.
+    /// Otherwise the script-path refund is signed, which is only valid after
+    /// the timeout.
+    #[pyo3(signature = (keys, fee, cooperative=true))]
+    pub(crate) fn sign_refund(&self, py: Python, keys: Vec<u8>, fee: u64, cooperative: bool) -> PyResult<String> {
+        Ok(self.refund(py, keys, fee, cooperative, false)?.0)
+    }
+}
+
+impl LBtcSwapTx {
+    // Signed refund and whether it is cooperative; the script path is only
+    // used without a client, or when the swap expired and Boltz did not
+    // cooperate
+    pub(crate) fn refund(&self, py: Python, keys: Vec<u8>, fee: u64, cooperative: bool, expired: bool) -> PyResult<(String, bool)> {
+        let keys = parse_keypair(keys)?;
+
+        if cooperative {
+            if let Some(client) = &self.client {
+                match self.cooperative_refund(py, client, &keys, fee) {
+                    Ok(tx) => return Ok((tx, true)),
+                    Err(_) if expired => {}
+                    Err(err) => return Err(err),
+                }
+            }
+        }
+
+        let tx = handle_boltz_error::<SigningError, _>(
+            "could not sign refund transaction",
+            self.tx.sign_refund(&keys, Amount::from_sat(fee), None),
+        )?;
+
+        Ok((serialize_hex(&tx), false))
+    }
+
+    fn cooperative_refund(&self, py: Python, client: &Py<Client>, keys: &Keypair, fee: u64) -> PyResult<String> {
+        let mut tx = handle_boltz_error::<SigningError, _>(
+            "could not sign refund transaction",
+            self.tx.sign_refund(keys, Amount::from_sat(fee), None),
+        )?;
+        // the key path does not wait for the timeout
+        tx.lock_time = LockTime::ZERO;
+
+        let client = client.borrow(py);
+        let api = client.boltz();
+        let tx = py.allow_threads(|| self.cooperative_spend(api, keys, tx, None))?;
+
+        Ok(serialize_hex(&tx))
+    }
+
+    // Replaces the script-path witness of tx with the key-path signature
+    // aggregated with a partial signature of Boltz; the blinded outputs are
+    // kept as they are
+    fn cooperative_spend(&self, api: &BoltzApi, keys: &Keypair, mut tx: Transaction, preimage: Option<String>) -> PyResult<Transaction> {
+        tx.input[0].witness.script_witness = Vec::new();
+        let sighash = handle_rust_error_as::<SigningError, _, _>(
+            "could not compute signature hash",
+            SighashCache::new(&tx).taproot_key_spend_signature_hash(
+                0,
+                &Prevouts::All(&[&self.tx.funding_utxo]),
+                SchnorrSighashType::Default,
+                self.tx.genesis_hash,
+            ),
+        )?;
+        let spend_info = handle_boltz_error::<ScriptError, _>(
+            "could not compute taproot spend info",
+            self.tx.swap_script.taproot_spendinfo(),
+        )?;
+
+        let signer = KeyPathSigner::new(
+            self.tx.swap_script.musig_keyagg_cache(),
+            spend_info.tap_tweak().as_byte_array(),
+            keys,
+            sighash.to_byte_array(),
+        )?;
+        let res = api.get_partial_signature(
+            &self.tx.kind,
+            &self.tx.swap_script.swap_type,
+            &self.swap_id,
+            signer.pub_nonce(),
+            serialize_hex(&tx),
+            preimage,
+        )?;
+        let signature = signer.sign(keys, &res.pub_nonce, &res.partial_signature)?;
+        tx.input[0].witness.script_witness = vec![signature.to_vec()];
+
+        Ok(tx)
+    }
+
+    fn new(
+        py: Python,
+        kind: SwapTxKind,
+        script: LBtcSwapScript,
+        output_address: String,
+        lockup_tx: Option<LockupTx>,
+        network: String,
+        client: Option<Py<Client>>,
+        genesis_hash: Option<String>,
+        backend: Option<Backend>,
+    ) -> PyResult<Self> {
+        let chain = parse_liquid_chain(network.as_str())?;
+        let output_address = handle_rust_error_as::<InvalidAddressError, _, _>(
+            "could not parse output address",
+            Address::from_str(output_address.as_str()),
+        )?;
+        if output_address.params != parse_liquid_address_params(network.as_str())? {
+            return Err(new_python_error::<InvalidAddressError>("output address is not valid for network"));
+        }
+        let lockup_tx = match (lockup_tx, backend) {
+            (Some(lockup_tx), _) => parse_liquid_transaction(lockup_tx.into_hex())?,
+            (None, Some(backend)) => {
+                let backend = backend.get();
+                if !backend.is_liquid() {
+                    return Err(PyValueError::new_err("backend is not connected to a Liquid server"));
+                }
+
+                let script_pubkey = script.lockup_script(chain)?;
+                let tx = py.allow_threads(|| backend.lockup_transaction(script_pubkey.as_bytes()))?;
+                handle_rust_error("could not parse transaction", deserialize(tx.as_slice()))?
+            }
+            (None, None) => return Err(PyValueError::new_err("either lockup_tx or backend is required")),
+        };
+        let (funding_outpoint, funding_utxo) = script.find_lockup_utxo(chain, &lockup_tx)?;
+
+        Ok(LBtcSwapTx {
+            tx: boltz_client::LBtcSwapTx {
+                kind,
+                swap_script: script.script,
+                output_address,
+                funding_outpoint,
+                funding_utxo,
+                genesis_hash: genesis_hash(chain, genesis_hash)?,
+            },
+            swap_id: script.swap_id,
+            client,
+        })
+    }
+}
@@ -0,0 +1,534 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use boltz_client::boltz::Side;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::{PyAnyMethods, PyDictMethods};
+use pyo3::types::PyDict;
+use pyo3::{pyclass, pymethods, Bound, Py, PyErr, PyObject, PyRef, PyResult, Python};
+
+use crate::client::backend::Backend;
+use crate::client::boltz::Client;
+use crate::client::ws::SwapStatusStream;
+use crate::store::swap_store::{SharedStore, StoredSwap, SwapStore, TransactionKind};
+use crate::swaps::bitcoin::BtcSwapTx;
+use crate::swaps::liquid::LBtcSwapTx;
+use crate::swaps::refund::spend_fee;
+use crate::swaps::rescue::{RescueSwap, SwapScript};
+use crate::types::status::{SwapStatus, SwapUpdate};
+use crate::types::transaction::LockupTx;
+use crate::utils::derivation::SwapKeyDeriver;
+use crate::utils::errors::{handle_rust_error, new_python_error, BoltzError};
+use crate::utils::network::parse_chain;
+use crate::utils::preimage::PreimageArg;
+
+const POLL_TIMEOUT: Duration = Duration::from_secs(1);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct SwapEvent {
+    #[pyo3(get)]
+    pub kind: String,
+    #[pyo3(get)]
+    pub swap_id: Option<String>,
+    #[pyo3(get)]
+    pub status: Option<SwapStatus>,
+    #[pyo3(get)]
+    pub transaction_id: Option<String>,
+    #[pyo3(get)]
+    pub error: Option<String>,
+}
+
+impl SwapEvent {
+    fn new(kind: &str, swap: &StoredSwap, transaction_id: Option<String>) -> Self {
+        SwapEvent {
+            kind: kind.to_string(),
+            swap_id: Some(swap.id.clone()),
+            status: swap.status,
+            transaction_id,
+            error: None,
+        }
+    }
+
+    fn error(swap_id: Option<&str>, err: &PyErr) -> Self {
+        SwapEvent {
+            kind: "error".to_string(),
+            swap_id: swap_id.map(str::to_string),
+            status: None,
+            transaction_id: None,
+            error: Some(err.to_string()),
+        }
+    }
+}
+
+#[pymethods]
+impl SwapEvent {
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("kind", self.kind.clone())?;
+        dict.set_item("swap_id", self.swap_id.clone())?;
+        dict.set_item("status", self.status.map(|status| status.as_str()))?;
+        dict.set_item("transaction_id", self.transaction_id.clone())?;
+        dict.set_item("error", self.error.clone())?;
+        Ok(dict)
+    }
+}
+
+struct Worker {
+    client: Py<Client>,
+    backend: PyObject,
+    store: SharedStore,
+    deriver: Option<SwapKeyDeriver>,
+    addresses: HashMap<String, String>,
+    network: String,
+    fee_rate: Option<f64>,
+    cooperative: bool,
+    callback: Option<PyObject>,
+    refund_interval: Duration,
+    // submarine swaps whose claim we already signed
+    claim_signed: Mutex<HashSet<String>>,
+}
+
+// The worker runs on its own thread and only takes the GIL where Python
+// objects are touched: callbacks, the client and backend and the swap
+// transactions. Store queries, network calls and waiting for updates run
+// without it.
+impl Worker {
+    fn emit(&self, event: SwapEvent) {
+        if let Some(callback) = &self.callback {
+            Python::with_gil(|py| {
+                if let Err(err) = callback.call1(py, (event,)) {
+                    err.write_unraisable_bound(py, None);
+                }
+            });
+        }
+    }
+
+    // The backend is only used for the chain it is connected to, everything
+    // else goes through the Boltz API
+    fn backend_for<'py>(&self, py: Python<'py>, asset: &str) -> PyResult<Option<Backend<'py>>> {
+        let backend: Backend = self.backend.bind(py).extract()?;
+        if backend.get().is_liquid() == (asset == "L-BTC") {
+            Ok(Some(backend))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn address(&self, asset: &str) -> PyResult<String> {
+        self.addresses
+            .get(asset)
+            .cloned()
+            .ok_or_else(|| new_python_error::<BoltzError>(&format!("no address configured for {}", asset)))
+    }
+
+    fn fee(&self, asset: &str) -> PyResult<u64> {
+        Python::with_gil(|py| {
+            let backend = self.backend_for(py, asset)?;
+            spend_fee(py, backend.as_ref().map(|backend| backend.get()), asset, self.fee_rate)
+        })
+    }
+
+    fn broadcast(&self, asset: &str, tx_hex: String) -> PyResult<String> {
+        Python::with_gil(|py| {
+            if let Some(backend) = self.backend_for(py, asset)? {
+                let tx = handle_rust_error("could not decode transaction hex", hex::decode(tx_hex.as_str()))?;
+                let backend = backend.get();
+                return py.allow_threads(|| backend.broadcast(tx.as_slice()));
+            }
+
+            let client = self.client.borrow(py);
+            let api = client.boltz();
+            py.allow_threads(|| api.broadcast_transaction(asset.to_string(), tx_hex))
+        })
+    }
+
+    fn lockup_transaction(&self, swap: &StoredSwap) -> PyResult<String> {
+        let asset = swap.asset_send.as_str();
+        Python::with_gil(|py| match &swap.funding_txid {
+            Some(txid) => {
+                if let Some(backend) = self.backend_for(py, asset)? {
+                    let backend = backend.get();
+                    return Ok(hex::encode(py.allow_threads(|| backend.transaction(txid.as_str()))?));
+                }
+                let client = self.client.borrow(py);
+                let api = client.boltz();
+                py.allow_threads(|| api.get_transaction(asset.to_string(), txid.clone()))
+            }
+            None if swap.swap_type == "submarine" => {
+                let client = self.client.borrow(py);
+                let api = client.boltz();
+                Ok(py.allow_threads(|| api.get_submarine_transaction(swap.id.clone()))?.hex)
+            }
+            None => Err(new_python_error::<BoltzError>("lockup transaction of swap is unknown")),
+        })
+    }
+
+    fn heights(&self, assets: HashSet<String>) -> PyResult<HashMap<String, u32>> {
+        Python::with_gil(|py| {
+            let mut heights = HashMap::new();
+            let mut boltz_heights = None;
+            for asset in assets {
+                let height = match self.backend_for(py, asset.as_str())? {
+                    Some(backend) => {
+                        let backend = backend.get();
+                        py.allow_threads(|| backend.tip_height())?
+                    }
+                    None => {
+                        if boltz_heights.is_none() {
+                            let client = self.client.borrow(py);
+                            let api = client.boltz();
+                            boltz_heights = Some(py.allow_threads(|| api.get_height())?);
+                        }
+                        let boltz_heights = boltz_heights.as_ref().expect("heights were fetched");
+                        if asset == "L-BTC" {
+                            boltz_heights.lbtc
+                        } else {
+                            boltz_heights.btc
+                        }
+                    }
+                };
+                heights.insert(asset, height);
+            }
+
+            Ok(heights)
+        })
+    }
+
+    // Repeated updates of a status are acted upon again, so that failed claims
+    // and refunds are retried; whether they succeeded is tracked with the
+    // transaction IDs in the store and claim_signed.
+    fn handle_update(&self, update: &SwapUpdate) -> PyResult<()> {
+        // statuses we do not know cannot be acted upon and would overwrite the
+        // last known one in the store
+        if update.status == SwapStatus::Unknown {
+            return Ok(());
+        }
+
+        let stored = self.store.get(update.id.as_str())?;
+        let swap = if stored.status == Some(update.status) {
+            stored
+        } else {
+            let swap = self
+                .store
+                .update_status(update.id.as_str(), update.status, update.transaction_id.as_deref())?;
+            self.emit(SwapEvent::new("status", &swap, update.transaction_id.clone()));
+            swap
+        };
+
+        let zero_conf = update.zero_conf_rejected != Some(true);
+        match (swap.swap_type.as_str(), update.status) {
+            ("reverse", SwapStatus::TransactionMempool) if zero_conf => self.claim(&swap, update.transaction_hex.clone()),
+            ("reverse", SwapStatus::TransactionConfirmed) => self.claim(&swap, update.transaction_hex.clone()),
+            ("chain", SwapStatus::TransactionServerMempool | SwapStatus::TransactionServerConfirmed) => {
+                self.claim(&swap, update.transaction_hex.clone())
+            }
+            ("submarine", SwapStatus::TransactionClaimPending) => self.sign_submarine_claim(&swap),
+            ("submarine" | "chain", status) if status.requires_refund() => self.refund(&swap, false),
+            _ => Ok(()),
+        }
+    }
+
+    fn claim(&self, swap: &StoredSwap, lockup_hex: Option<String>) -> PyResult<()> {
+        if swap.claim_txid.is_some() {
+            return Ok(());
+        }
+
+        let rescue = swap.rescue_swap()?;
+        let preimage = rescue
+            .preimage()
+            .ok_or_else(|| new_python_error::<BoltzError>("swap has no preimage to claim with"))?;
+        let (script, keys) = rescue.swap_script(self.deriver.as_ref(), Side::Claim)?;
+        let asset = swap.asset_receive.as_str();
+        let lockup_hex = match lockup_hex {
+            Some(lockup_hex) => lockup_hex,
+            None => Python::with_gil(|py| {
+                let client = self.client.borrow(py);
+                let api = client.boltz();
+                py.allow_threads(|| api.get_swap_transaction(swap.id.clone()))
+            })?
+            .hex,
+        };
+        let lockup_tx = Some(LockupTx::Hex(lockup_hex));
+        let address = self.address(asset)?;
+        let fee = self.fee(asset)?;
+
+        let tx = Python::with_gil(|py| {
+            let client = Some(self.client.clone_ref(py));
+            match script {
+                SwapScript::Btc(script) => {
+                    let tx = BtcSwapTx::new_claim(py, script, address, lockup_tx, self.network.clone(), client, None)?;
+                    tx.sign_claim(py, keys, PreimageArg::Hex(preimage), fee, self.cooperative, true)
+                }
+                SwapScript::Liquid(script) => {
+                    let tx = LBtcSwapTx::new_claim(py, script, address, lockup_tx, self.network.clone(), client, None, None)?;
+                    tx.sign_claim(py, keys, PreimageArg::Hex(preimage), fee, self.cooperative, true)
+                }
+            }
+        })?;
+        let txid = self.broadcast(asset, tx)?;
+
+        let swap = self.store.set_transaction(swap.id.as_str(), TransactionKind::Claim, txid.as_str())?;
+        self.emit(SwapEvent::new("claimed", &swap, Some(txid)));
+        Ok(())
+    }
+
+    // Boltz can only claim submarine swaps cooperatively on Bitcoin, on Liquid
+    // it falls back to the script path
+    fn sign_submarine_claim(&self, swap: &StoredSwap) -> PyResult<()> {
+        if !self.cooperative || self.claim_signed.lock().expect("claim lock poisoned").contains(&swap.id) {
+            return Ok(());
+        }
+
+        let rescue = swap.rescue_swap()?;
+        let invoice = rescue
+            .invoice()
+            .ok_or_else(|| new_python_error::<BoltzError>("swap has no invoice to verify the preimage with"))?;
+        if let (SwapScript::Btc(script), keys) = rescue.swap_script(self.deriver.as_ref(), Side::Lockup)? {
+            Python::with_gil(|py| script.sign_submarine_claim(py, self.client.borrow(py), keys, invoice))?;
+            self.claim_signed.lock().expect("claim lock poisoned").insert(swap.id.clone());
+            self.emit(SwapEvent::new("claim_signed", swap, None));
+        }
+
+        Ok(())
+    }
+
+    // Expired swaps fall back to the script path when Boltz does not cooperate
+    fn refund(&self, swap: &StoredSwap, expired: bool) -> PyResult<()> {
+        if swap.refund_txid.is_some() {
+            return Ok(());
+        }
+
+        let rescue = swap.rescue_swap()?;
+        let (script, keys) = rescue.swap_script(self.deriver.as_ref(), Side::Lockup)?;
+        let asset = swap.asset_send.as_str();
+        let lockup_tx = Some(LockupTx::Hex(self.lockup_transaction(swap)?));
+        let address = self.address(asset)?;
+        let fee = self.fee(asset)?;
+
+        let (tx, _) = Python::with_gil(|py| {
+            let client = Some(self.client.clone_ref(py));
+            match script {
+                SwapScript::Btc(script) => BtcSwapTx::new_refund(py, script, address, lockup_tx, self.network.clone(), client, None)?
+                    .refund(py, keys, fee, self.cooperative, expired),
+                SwapScript::Liquid(script) => {
+                    LBtcSwapTx::new_refund(py, script, address, lockup_tx, self.network.clone(), client, None, None)?
+                        .refund(py, keys, fee, self.cooperative, expired)
+                }
+            }
+        })?;
+        let txid = self.broadcast(asset, tx)?;
+
+        let swap = self.store.set_transaction(swap.id.as_str(), TransactionKind::Refund, txid.as_str())?;
+        self.emit(SwapEvent::new("refunded", &swap, Some(txid)));
+        Ok(())
+    }
+
+    // Failed swaps are refunded right away, the others once the chain they
+    // were locked up on reached their timeout
+    fn check_refunds(&self) -> PyResult<Vec<String>> {
+        let candidates = self.store.needing_refund_at(|_| Some(u32::MAX))?;
+        let heights = self.heights(candidates.iter().map(|swap| swap.asset_send.clone()).collect())?;
+
+        let mut refunded = Vec::new();
+        for swap in self.store.needing_refund_at(|asset| heights.get(asset).copied())? {
+            let expired = swap
+                .timeout_block_height
+                .zip(heights.get(&swap.asset_send))
+                .is_some_and(|(timeout, height)| *height >= timeout);
+            match self.refund(&swap, expired) {
+                Ok(()) => refunded.push(swap.id),
+                Err(err) => self.emit(SwapEvent::error(Some(swap.id.as_str()), &err)),
+            }
+        }
+
+        Ok(refunded)
+    }
+
+    fn connect(&self) -> PyResult<SwapStatusStream> {
+        let swap_ids = self.store.pending()?.into_iter().map(|swap| swap.id).collect();
+        let stream = Python::with_gil(|py| {
+            let client = self.client.borrow(py);
+            let api = client.boltz();
+            py.allow_threads(|| api.subscribe_swap_status(swap_ids))
+        })?;
+        stream.set_read_timeout(Some(POLL_TIMEOUT))?;
+
+        Ok(stream)
+    }
+}
+
+fn run(worker: Arc<Worker>, stream: Arc<Mutex<Option<SwapStatusStream>>>, stop: Arc<AtomicBool>) {
+    let mut last_check: Option<Instant> = None;
+
+    while !stop.load(Ordering::SeqCst) {
+        let current = stream.lock().expect("stream lock poisoned").clone();
+        let reconnect = match current.map(|current| (current.poll_update(), current.is_closed())) {
+            Some((Ok(Some(update)), _)) => {
+                if let Err(err) = worker.handle_update(&update) {
+                    worker.emit(SwapEvent::error(Some(update.id.as_str()), &err));
+                }
+                false
+            }
+            Some((Ok(None), closed)) => closed,
+            Some((Err(err), _)) => {
+                worker.emit(SwapEvent::error(None, &err));
+                true
+            }
+            None => true,
+        };
+
+        if reconnect {
+            *stream.lock().expect("stream lock poisoned") = None;
+            let deadline = Instant::now() + RECONNECT_DELAY;
+            while Instant::now() < deadline && !stop.load(Ordering::SeqCst) {
+                thread::sleep(POLL_TIMEOUT);
+            }
+            match worker.connect() {
+                Ok(connected) => *stream.lock().expect("stream lock poisoned") = Some(connected),
+                Err(err) => worker.emit(SwapEvent::error(None, &err)),
+            }
+        }
+
+        if last_check.map_or(true, |last_check| last_check.elapsed() >= worker.refund_interval) {
+            if let Err(err) = worker.check_refunds() {
+                worker.emit(SwapEvent::error(None, &err));
+            }
+            last_check = Some(Instant::now());
+        }
+    }
+}
+
+#[pyclass]
+pub struct SwapManager {
+    worker: Arc<Worker>,
+    stream: Arc<Mutex<Option<SwapStatusStream>>>,
+    stop: Arc<AtomicBool>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[pymethods]
+impl SwapManager {
+    /// Addresses map the assets BTC and L-BTC to the address claims and
+    /// refunds on that chain are sent to.
+    #[new]
+    #[pyo3(signature = (
+        client,
+        backend,
+        store,
+        network,
+        addresses,
+        deriver=None,
+        fee_rate=None,
+        cooperative=true,
+        callback=None,
+        refund_interval=60,
+    ))]
+    pub fn new(
+        py: Python,
+        client: Py<Client>,
+        backend: PyObject,
+        store: PyRef<SwapStore>,
+        network: String,
+        addresses: HashMap<String, String>,
+        deriver: Option<SwapKeyDeriver>,
+        fee_rate: Option<f64>,
+        cooperative: bool,
+        callback: Option<PyObject>,
+        refund_interval: u64,
+    ) -> PyResult<Self> {
+        backend.bind(py).extract::<Backend>()?;
+        for asset in addresses.keys() {
+            parse_chain(asset.as_str(), network.as_str())?;
+        }
+
+        Ok(SwapManager {
+            worker: Arc::new(Worker {
+                client,
+                backend,
+                store: store.shared(),
+                deriver,
+                addresses,
+                network,
+                fee_rate,
+                cooperative,
+                callback,
+                refund_interval: Duration::from_secs(refund_interval),
+                claim_signed: Mutex::new(HashSet::new()),
+            }),
+            stream: Arc::new(Mutex::new(None)),
+            stop: Arc::new(AtomicBool::new(false)),
+            handle: Mutex::new(None),
+        })
+    }
+
+    #[getter]
+    pub fn is_running(&self) -> bool {
+        self.handle.lock().expect("handle lock poisoned").is_some()
+    }
+
+    /// Stores the swap and subscribes to its status updates when the manager
+    /// is running.
+    pub fn add_swap(&self, py: Python, swap: RescueSwap) -> PyResult<StoredSwap> {
+        let stored = self.worker.store.add(&swap, None)?;
+        if let Some(stream) = self.stream.lock().expect("stream lock poisoned").clone() {
+            let swap_ids = vec![stored.id.clone()];
+            py.allow_threads(|| stream.subscribe(swap_ids))?;
+        }
+
+        Ok(stored)
+    }
+
+    pub fn process_update(&self, py: Python, update: SwapUpdate) -> PyResult<()> {
+        py.allow_threads(|| self.worker.handle_update(&update))
+    }
+
+    /// Refunds failed and timed out swaps and returns the IDs of the refunded
+    /// ones.
+    pub fn check_refunds(&self, py: Python) -> PyResult<Vec<String>> {
+        py.allow_threads(|| self.worker.check_refunds())
+    }
+
+    pub fn start(&self, py: Python) -> PyResult<()> {
+        let mut handle = self.handle.lock().expect("handle lock poisoned");
+        if handle.is_some() {
+            return Err(PyValueError::new_err("swap manager is already running"));
+        }
+
+        let worker = self.worker.clone();
+        let stream = py.allow_threads(|| worker.connect())?;
+        *self.stream.lock().expect("stream lock poisoned") = Some(stream);
+        self.stop.store(false, Ordering::SeqCst);
+
+        let stream = self.stream.clone();
+        let stop = self.stop.clone();
+        *handle = Some(thread::spawn(move || run(worker, stream, stop)));
+        Ok(())
+    }
+
+    pub fn stop(&self, py: Python) {
+        self.stop.store(true, Ordering::SeqCst);
+        let handle = self.handle.lock().expect("handle lock poisoned").take();
+        if let Some(handle) = handle {
+            let _ = py.allow_threads(|| handle.join());
+        }
+        *self.stream.lock().expect("stream lock poisoned") = None;
+    }
+}
+
+impl Drop for SwapManager {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        let handle = self.handle.get_mut().expect("handle lock poisoned").take();
+        // a callback dropping the last reference runs on the worker thread,
+        // which stops on its own
+        if let Some(handle) = handle.filter(|handle| handle.thread().id() != thread::current().id()) {
+            // the worker needs the GIL for callbacks until it saw the flag
+            let _ = Python::with_gil(|py| py.allow_threads(|| handle.join()));
+        }
+    }
+}
@@ -1 +1,7 @@
 pub mod bitcoin;
+pub mod liquid;
+pub mod manager;
+pub mod musig;
+pub mod refund;
+pub mod rescue;
+pub mod state;
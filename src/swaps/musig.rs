@@ -0,0 +1,127 @@
+use bitcoin::key::rand::{thread_rng, RngCore};
+use boltz_client::elements::secp256k1_zkp::{
+    Keypair, Message, MusigAggNonce, MusigKeyAggCache, MusigPartialSignature, MusigPubNonce,
+    MusigSecNonce, MusigSession, MusigSessionId, Secp256k1, SecretKey,
+};
+use pyo3::PyResult;
+
+use crate::utils::errors::{handle_rust_error_as, SigningError};
+
+fn tweak_key_agg_cache(key_agg_cache: &mut MusigKeyAggCache, tweak: &[u8]) -> PyResult<()> {
+    let tweak = handle_rust_error_as::<SigningError, _, _>("could not parse taproot tweak", SecretKey::from_slice(tweak))?;
+    handle_rust_error_as::<SigningError, _, _>(
+        "could not tweak aggregated key",
+        key_agg_cache.pubkey_xonly_tweak_add(&Secp256k1::new(), tweak),
+    )?;
+    Ok(())
+}
+
+fn parse_pub_nonce(nonce: &str) -> PyResult<MusigPubNonce> {
+    let nonce = handle_rust_error_as::<SigningError, _, _>("could not decode public nonce", hex::decode(nonce))?;
+    handle_rust_error_as::<SigningError, _, _>(
+        "could not parse public nonce",
+        MusigPubNonce::from_slice(nonce.as_slice()),
+    )
+}
+
+fn generate_nonce(key_agg_cache: &MusigKeyAggCache, keys: &Keypair, msg: Message) -> PyResult<(MusigSecNonce, MusigPubNonce)> {
+    let mut extra_rand = [0u8; 32];
+    thread_rng().fill_bytes(&mut extra_rand);
+    handle_rust_error_as::<SigningError, _, _>(
+        "could not generate nonce",
+        key_agg_cache.nonce_gen(
+            &Secp256k1::new(),
+            MusigSessionId::new(&mut thread_rng()),
+            keys.public_key(),
+            msg,
+            Some(extra_rand),
+        ),
+    )
+}
+
+pub(crate) fn partial_sign(
+    mut key_agg_cache: MusigKeyAggCache,
+    tweak: &[u8],
+    keys: &Keypair,
+    their_nonce: &str,
+    transaction_hash: &str,
+) -> PyResult<(MusigPartialSignature, MusigPubNonce)> {
+    let secp = Secp256k1::new();
+    tweak_key_agg_cache(&mut key_agg_cache, tweak)?;
+
+    let transaction_hash = handle_rust_error_as::<SigningError, _, _>("could not decode transaction hash", hex::decode(transaction_hash))?;
+    let msg = handle_rust_error_as::<SigningError, _, _>(
+        "could not parse transaction hash",
+        Message::from_digest_slice(transaction_hash.as_slice()),
+    )?;
+
+    let their_nonce = parse_pub_nonce(their_nonce)?;
+    let (sec_nonce, our_nonce) = generate_nonce(&key_agg_cache, keys, msg)?;
+
+    let agg_nonce = MusigAggNonce::new(&secp, &[their_nonce, our_nonce]);
+    let session = MusigSession::new(&secp, &key_agg_cache, agg_nonce, msg);
+    let partial_sig = handle_rust_error_as::<SigningError, _, _>(
+        "could not create partial signature",
+        session.partial_sign(&secp, sec_nonce, keys, &key_agg_cache),
+    )?;
+
+    Ok((partial_sig, our_nonce))
+}
+
+/// Our half of a cooperative key-path spend of a swap output: the nonce is
+/// sent to Boltz along with the transaction, and the partial signature Boltz
+/// answers with is aggregated with ours into the final Schnorr signature.
+pub(crate) struct KeyPathSigner {
+    key_agg_cache: MusigKeyAggCache,
+    msg: Message,
+    sec_nonce: MusigSecNonce,
+    pub_nonce: MusigPubNonce,
+}
+
+impl KeyPathSigner {
+    pub fn new(mut key_agg_cache: MusigKeyAggCache, tweak: &[u8], keys: &Keypair, sighash: [u8; 32]) -> PyResult<Self> {
+        tweak_key_agg_cache(&mut key_agg_cache, tweak)?;
+        let msg = Message::from_digest(sighash);
+        let (sec_nonce, pub_nonce) = generate_nonce(&key_agg_cache, keys, msg)?;
+
+        Ok(KeyPathSigner {
+            key_agg_cache,
+            msg,
+            sec_nonce,
+            pub_nonce,
+        })
+    }
+
+    pub fn pub_nonce(&self) -> String {
+        hex::encode(self.pub_nonce.serialize())
+    }
+
+    pub fn sign(self, keys: &Keypair, their_nonce: &str, their_partial_sig: &str) -> PyResult<[u8; 64]> {
+        let secp = Secp256k1::new();
+        let their_nonce = parse_pub_nonce(their_nonce)?;
+        let their_partial_sig = handle_rust_error_as::<SigningError, _, _>(
+            "could not decode partial signature",
+            hex::decode(their_partial_sig),
+        )?;
+        let their_partial_sig = handle_rust_error_as::<SigningError, _, _>(
+            "could not parse partial signature",
+            MusigPartialSignature::from_slice(their_partial_sig.as_slice()),
+        )?;
+
+        let agg_nonce = MusigAggNonce::new(&secp, &[their_nonce, self.pub_nonce]);
+        let session = MusigSession::new(&secp, &self.key_agg_cache, agg_nonce, self.msg);
+        let our_partial_sig = handle_rust_error_as::<SigningError, _, _>(
+            "could not create partial signature",
+            session.partial_sign(&secp, self.sec_nonce, keys, &self.key_agg_cache),
+        )?;
+
+        // An invalid partial signature of Boltz only shows in the aggregate
+        let signature = session.partial_sig_agg(&[their_partial_sig, our_partial_sig]);
+        handle_rust_error_as::<SigningError, _, _>(
+            "invalid partial signature",
+            secp.verify_schnorr(&signature, &self.msg, &self.key_agg_cache.agg_pk()),
+        )?;
+
+        Ok(signature.serialize())
+    }
+}
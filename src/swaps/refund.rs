@@ -0,0 +1,279 @@
+use boltz_client::boltz::Side;
+use pyo3::prelude::{PyAnyMethods, PyDictMethods};
+use pyo3::types::PyDict;
+use pyo3::{pyclass, pymethods, Bound, Py, PyErr, PyObject, PyResult, Python};
+
+use crate::client::backend::{Backend, ChainBackend};
+use crate::client::boltz::Client;
+use crate::store::swap_store::StoredSwap;
+use crate::swaps::bitcoin::{parse_transaction, BtcSwapTx};
+use crate::swaps::liquid::{parse_liquid_transaction, LBtcSwapTx};
+use crate::swaps::rescue::SwapScript;
+use crate::types::transaction::LockupTx;
+use crate::utils::address::validate_address;
+use crate::utils::derivation::SwapKeyDeriver;
+use crate::utils::errors::{new_python_error, ApiError, InvalidAddressError};
+use crate::utils::network::parse_chain;
+
+// Estimated vsize of claims and refunds through the script path, which is an
+// upper bound for the cooperative key path spends
+const BTC_SPEND_VSIZE: f64 = 170.0;
+const LIQUID_SPEND_VSIZE: f64 = 1400.0;
+
+// Fee rates in sat/vbyte when neither a fee rate is configured nor a backend
+// is connected to the chain of the transaction
+const DEFAULT_BTC_FEE_RATE: f64 = 2.0;
+const DEFAULT_LIQUID_FEE_RATE: f64 = 0.1;
+
+/// Absolute fee of a claim or refund of a swap on the chain of asset.
+pub(crate) fn spend_fee(py: Python, backend: Option<&dyn ChainBackend>, asset: &str, fee_rate: Option<f64>) -> PyResult<u64> {
+    let liquid = asset == "L-BTC";
+    let fee_rate = match (fee_rate, backend) {
+        (Some(fee_rate), _) => fee_rate,
+        (None, Some(backend)) => py.allow_threads(|| backend.estimate_fee(2))?,
+        (None, None) if liquid => DEFAULT_LIQUID_FEE_RATE,
+        (None, None) => DEFAULT_BTC_FEE_RATE,
+    };
+    let vsize = if liquid { LIQUID_SPEND_VSIZE } else { BTC_SPEND_VSIZE };
+
+    Ok((fee_rate * vsize).ceil() as u64)
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct RefundResult {
+    #[pyo3(get)]
+    pub swap_id: String,
+    #[pyo3(get)]
+    pub status: String,
+    #[pyo3(get)]
+    pub reason: Option<String>,
+    #[pyo3(get)]
+    pub current_height: u32,
+    #[pyo3(get)]
+    pub timeout_block_height: Option<u32>,
+    #[pyo3(get)]
+    pub lockup_txid: Option<String>,
+    #[pyo3(get)]
+    pub amount: Option<u64>,
+    #[pyo3(get)]
+    pub cooperative: bool,
+    #[pyo3(get)]
+    pub transaction_id: Option<String>,
+    #[pyo3(get)]
+    pub transaction_hex: Option<String>,
+}
+
+impl RefundResult {
+    fn new(swap: &StoredSwap, current_height: u32) -> Self {
+        RefundResult {
+            swap_id: swap.id.clone(),
+            status: "skipped".to_string(),
+            reason: None,
+            current_height,
+            timeout_block_height: swap.timeout_block_height,
+            lockup_txid: None,
+            amount: None,
+            cooperative: false,
+            transaction_id: None,
+            transaction_hex: None,
+        }
+    }
+
+    fn with_status(mut self, status: &str, reason: Option<&str>) -> Self {
+        self.status = status.to_string();
+        self.reason = reason.map(str::to_string);
+        self
+    }
+
+    fn expired(&self) -> bool {
+        self.timeout_block_height
+            .is_some_and(|timeout| self.current_height >= timeout)
+    }
+}
+
+#[pymethods]
+impl RefundResult {
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("swap_id", self.swap_id.clone())?;
+        dict.set_item("status", self.status.clone())?;
+        dict.set_item("reason", self.reason.clone())?;
+        dict.set_item("current_height", self.current_height)?;
+        dict.set_item("timeout_block_height", self.timeout_block_height)?;
+        dict.set_item("lockup_txid", self.lockup_txid.clone())?;
+        dict.set_item("amount", self.amount)?;
+        dict.set_item("cooperative", self.cooperative)?;
+        dict.set_item("transaction_id", self.transaction_id.clone())?;
+        dict.set_item("transaction_hex", self.transaction_hex.clone())?;
+        Ok(dict)
+    }
+}
+
+#[pyclass]
+pub struct RefundScanner {
+    backend: PyObject,
+    network: String,
+    address: String,
+    client: Option<Py<Client>>,
+    deriver: Option<SwapKeyDeriver>,
+    fee_rate: Option<f64>,
+    dry_run: bool,
+}
+
+impl RefundScanner {
+    // The backend is authoritative for the timelock, the height of Boltz is
+    // only used when the backend cannot be reached
+    fn current_height(&self, py: Python, backend: &dyn ChainBackend) -> PyResult<u32> {
+        let err = match py.allow_threads(|| backend.tip_height()) {
+            Ok(height) => return Ok(height),
+            Err(err) => err,
+        };
+        let Some(client) = &self.client else {
+            return Err(err);
+        };
+
+        let client = client.borrow(py);
+        let api = client.boltz();
+        let heights = py.allow_threads(|| api.get_height())?;
+        Ok(if backend.is_liquid() { heights.lbtc } else { heights.btc })
+    }
+
+    fn scan_swap(&self, py: Python, backend: &dyn ChainBackend, asset: &str, swap: &StoredSwap, mut result: RefundResult) -> PyResult<RefundResult> {
+        if swap.swap_type == "reverse" {
+            return Ok(result.with_status("skipped", Some("reverse swaps are claimed, not refunded")));
+        }
+        if swap.asset_send != asset {
+            return Ok(result.with_status("skipped", Some("swap was locked up on another chain")));
+        }
+        if swap.claim_txid.is_some() || swap.refund_txid.is_some() {
+            return Ok(result.with_status("skipped", Some("swap was already claimed or refunded")));
+        }
+        // Like SharedStore::needing_refund_at, swaps that did not fail are only
+        // refunded once they timed out
+        match swap.status {
+            Some(status) if status.is_final() && !status.requires_refund() => {
+                return Ok(result.with_status("skipped", Some("swap completed")));
+            }
+            Some(status) if status.is_final() => {}
+            _ if !result.expired() => {
+                return Ok(result.with_status("pending", Some("swap did not fail and the timeout was not reached")));
+            }
+            _ => {}
+        }
+
+        let rescue = swap.rescue_swap()?;
+        let (script, keys) = rescue.swap_script(self.deriver.as_ref(), Side::Lockup)?;
+        let script_pubkey = match &script {
+            SwapScript::Btc(script) => script.lockup_script()?.to_bytes(),
+            SwapScript::Liquid(script) => script.lockup_script(parse_chain(asset, self.network.as_str())?)?.to_bytes(),
+        };
+        let utxos = py.allow_threads(|| backend.utxos(script_pubkey.as_slice()))?;
+        let Some(utxo) = utxos.into_iter().next() else {
+            return Ok(result.with_status("unfunded", Some("lockup address has no unspent output")));
+        };
+        result.lockup_txid = Some(utxo.txid.clone());
+        result.amount = utxo.value;
+
+        let expired = result.expired();
+        if !expired && self.client.is_none() {
+            return Ok(result.with_status("pending", Some("timeout was not reached")));
+        }
+        // a real run tries Boltz first whenever there is a client, expired or not
+        if self.dry_run {
+            result.cooperative = self.client.is_some();
+            return Ok(result.with_status("would_refund", None));
+        }
+
+        let lockup_tx = Some(LockupTx::Hex(hex::encode(py.allow_threads(|| backend.transaction(utxo.txid.as_str()))?)));
+        let fee = spend_fee(py, Some(backend), asset, self.fee_rate)?;
+        let client = self.client.as_ref().map(|client| client.clone_ref(py));
+
+        let signed = match script {
+            SwapScript::Btc(script) => BtcSwapTx::new_refund(py, script, self.address.clone(), lockup_tx, self.network.clone(), client, None)?
+                .refund(py, keys, fee, true, expired),
+            SwapScript::Liquid(script) => {
+                LBtcSwapTx::new_refund(py, script, self.address.clone(), lockup_tx, self.network.clone(), client, None, None)?
+                    .refund(py, keys, fee, true, expired)
+            }
+        };
+        let (tx_hex, cooperative) = match signed {
+            Ok(signed) => signed,
+            // a refusal of Boltz before the timeout is no failure of the scan,
+            // transport errors are
+            Err(err) if err.is_instance_of::<ApiError>(py) => {
+                let reason = format!("Boltz did not cooperate and the timeout was not reached: {}", err);
+                return Ok(result.with_status("pending", Some(reason.as_str())));
+            }
+            Err(err) => return Err(err),
+        };
+
+        result.transaction_id = Some(if backend.is_liquid() {
+            parse_liquid_transaction(tx_hex.clone())?.txid().to_string()
+        } else {
+            parse_transaction(tx_hex.clone())?.compute_txid().to_string()
+        });
+        result.transaction_hex = Some(tx_hex);
+        result.cooperative = cooperative;
+        Ok(result.with_status("signed", None))
+    }
+}
+
+#[pymethods]
+impl RefundScanner {
+    /// Refunds are only built for swaps locked up on the chain of the backend
+    /// and sent to address; without a client only timed out swaps can be
+    /// refunded.
+    #[new]
+    #[pyo3(signature = (backend, network, address, client=None, deriver=None, fee_rate=None, dry_run=false))]
+    pub fn new(
+        py: Python,
+        backend: PyObject,
+        network: String,
+        address: String,
+        client: Option<Py<Client>>,
+        deriver: Option<SwapKeyDeriver>,
+        fee_rate: Option<f64>,
+        dry_run: bool,
+    ) -> PyResult<Self> {
+        let liquid = backend.bind(py).extract::<Backend>()?.get().is_liquid();
+        let asset = if liquid { "L-BTC" } else { "BTC" };
+        if !validate_address(asset.to_string(), network.clone(), address.clone())? {
+            return Err(new_python_error::<InvalidAddressError>("address is not valid for network"));
+        }
+
+        Ok(RefundScanner {
+            backend,
+            network,
+            address,
+            client,
+            deriver,
+            fee_rate,
+            dry_run,
+        })
+    }
+
+    #[getter]
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Signs refunds of the swaps with an unspent lockup output, cooperatively
+    /// first and through the script path once the timeout was reached. The
+    /// transactions are not broadcast.
+    pub fn scan(&self, py: Python, swaps: Vec<StoredSwap>) -> PyResult<Vec<RefundResult>> {
+        let backend: Backend = self.backend.bind(py).extract()?;
+        let backend = backend.get();
+        let asset = if backend.is_liquid() { "L-BTC" } else { "BTC" };
+        let current_height = self.current_height(py, backend)?;
+
+        Ok(swaps
+            .iter()
+            .map(|swap| {
+                let result = RefundResult::new(swap, current_height);
+                self.scan_swap(py, backend, asset, swap, result.clone())
+                    .unwrap_or_else(|err: PyErr| result.with_status("error", Some(err.to_string().as_str())))
+            })
+            .collect())
+    }
+}
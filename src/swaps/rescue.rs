@@ -0,0 +1,489 @@
+use boltz_client::boltz::{self, Side};
+use boltz_client::PublicKey;
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, IntoPy, Py, PyObject, PyRef, PyResult, Python};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use crate::client::boltz::Client;
+use crate::swaps::bitcoin::BtcSwapScript;
+use crate::swaps::liquid::{parse_side, LBtcSwapScript};
+use crate::types::chain::{ChainSwapDetails, CreateChainResponse};
+use crate::types::reverse::CreateReverseResponse;
+use crate::types::submarine::{CreateSubmarineResponse, SwapTree};
+use crate::utils::derivation::SwapKeyDeriver;
+use crate::utils::errors::{handle_boltz_error, handle_rust_error, new_python_error, BoltzError, ScriptError};
+use crate::utils::keys::{parse_keypair, parse_public_key};
+use crate::utils::network::parse_chain;
+
+const COULD_NOT_PARSE_RESCUE_FILE: &str = "could not parse rescue file";
+const COULD_NOT_REBUILD_SCRIPT: &str = "could not rebuild swap script";
+const COULD_NOT_RESTORE_SWAPS: &str = "could not restore swaps";
+
+fn tree_json(tree: &SwapTree) -> Value {
+    json!({
+        "claimLeaf": { "output": tree.claim_leaf.output, "version": tree.claim_leaf.version },
+        "refundLeaf": { "output": tree.refund_leaf.output, "version": tree.refund_leaf.version },
+    })
+}
+
+fn details_json(details: &ChainSwapDetails) -> Value {
+    json!({
+        "swapTree": tree_json(&details.swap_tree),
+        "lockupAddress": details.lockup_address,
+        "serverPublicKey": hex::encode(&details.server_public_key),
+        "timeoutBlockHeight": details.timeout_block_height,
+        "amount": details.amount,
+        "blindingKey": details.blinding_key,
+        "refundAddress": details.refund_address,
+        "claimAddress": details.claim_address,
+        "bip21": details.bip21,
+    })
+}
+
+fn check_asset(asset: &str) -> PyResult<()> {
+    parse_chain(asset, "main").map(|_| ())
+}
+
+// Our keys are stored either as index of the SwapKeyDeriver, like the web
+// app does, or as raw private key like older refund files did
+fn set_key(swap: &mut Value, name: &str, index: Option<u32>, private_key: Option<Vec<u8>>) {
+    if let Some(index) = index {
+        swap[format!("{}PrivateKeyIndex", name)] = json!(index);
+    }
+    if let Some(private_key) = private_key {
+        swap[format!("{}PrivateKey", name)] = json!(hex::encode(private_key));
+    }
+}
+
+// Details of a side as returned by the swap restore endpoint of Boltz
+fn restored_details(details: &Value) -> Value {
+    json!({
+        "swapTree": details["tree"],
+        "lockupAddress": details["lockupAddress"],
+        "serverPublicKey": details["serverPublicKey"],
+        "timeoutBlockHeight": details["timeoutBlockHeight"],
+        "amount": details["amount"].as_u64().unwrap_or_default(),
+        "blindingKey": details["blindingKey"],
+    })
+}
+
+// Maps a swap restored from Boltz onto the fields of our rescue files. Boltz
+// does not return invoices and BIP21s, the swap scripts are rebuilt from the
+// swap trees alone; preimages are derived like the web app does
+fn restored_swap(swap: &Value, deriver: &SwapKeyDeriver) -> PyResult<RescueSwap> {
+    let refund = &swap["refundDetails"];
+    let claim = &swap["claimDetails"];
+
+    let mut data = match swap["type"].as_str() {
+        Some("submarine") => json!({
+            "address": refund["lockupAddress"],
+            "bip21": "",
+            "expectedAmount": refund["amount"].as_u64().unwrap_or_default(),
+            "acceptZeroConf": false,
+            "claimPublicKey": refund["serverPublicKey"],
+            "swapTree": refund["tree"],
+            "timeoutBlockHeight": refund["timeoutBlockHeight"],
+            "blindingKey": refund["blindingKey"],
+            "refundPrivateKeyIndex": refund["keyIndex"],
+        }),
+        Some("reverse") => json!({
+            "invoice": "",
+            "lockupAddress": claim["lockupAddress"],
+            "refundPublicKey": claim["serverPublicKey"],
+            "swapTree": claim["tree"],
+            "timeoutBlockHeight": claim["timeoutBlockHeight"],
+            "onchainAmount": claim["amount"].as_u64().unwrap_or_default(),
+            "blindingKey": claim["blindingKey"],
+            "claimPrivateKeyIndex": claim["keyIndex"],
+        }),
+        Some("chain") => json!({
+            "lockupDetails": restored_details(refund),
+            "claimDetails": restored_details(claim),
+            "claimPrivateKeyIndex": claim["keyIndex"],
+            "refundPrivateKeyIndex": refund["keyIndex"],
+        }),
+        _ => return Err(new_python_error::<BoltzError>(&format!("{}: invalid swap type", COULD_NOT_RESTORE_SWAPS))),
+    };
+    data["type"] = swap["type"].clone();
+    data["id"] = swap["id"].clone();
+    data["assetSend"] = swap["from"].clone();
+    data["assetReceive"] = swap["to"].clone();
+    if let Some(index) = claim["keyIndex"].as_u64() {
+        data["preimage"] = json!(deriver.derive_preimage(index as u32)?.hex());
+    }
+
+    RescueSwap::from_value(data)
+}
+
+fn parse_field<T: DeserializeOwned>(value: &Value) -> PyResult<T> {
+    handle_rust_error(COULD_NOT_PARSE_RESCUE_FILE, serde_json::from_value(value.clone()))
+}
+
+pub(crate) enum SwapScript {
+    Btc(BtcSwapScript),
+    Liquid(LBtcSwapScript),
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct RescueSwap {
+    data: Value,
+}
+
+impl RescueSwap {
+    fn str_field(&self, name: &str) -> PyResult<String> {
+        self.data[name]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| new_python_error::<BoltzError>(&format!("{}: missing {}", COULD_NOT_PARSE_RESCUE_FILE, name)))
+    }
+
+    pub(crate) fn from_value(data: Value) -> PyResult<Self> {
+        let swap = RescueSwap { data };
+        swap.str_field("id")?;
+        match swap.str_field("type")?.as_str() {
+            "submarine" | "reverse" | "chain" => Ok(swap),
+            _ => Err(new_python_error::<BoltzError>(&format!("{}: invalid swap type", COULD_NOT_PARSE_RESCUE_FILE))),
+        }
+    }
+
+    pub(crate) fn invoice(&self) -> Option<String> {
+        self.data["invoice"].as_str().map(str::to_string)
+    }
+
+    /// Rebuilds the swap script of a side along with our private key for it.
+    pub(crate) fn swap_script(&self, deriver: Option<&SwapKeyDeriver>, side: Side) -> PyResult<(SwapScript, Vec<u8>)> {
+        let private_key = self.key_for_side(&side, deriver)?;
+        let keys = parse_keypair(private_key.clone())?;
+        let our_pubkey = parse_public_key(keys.public_key().serialize().to_vec())?;
+        let swap_id = self.str_field("id")?;
+
+        let asset = match side {
+            Side::Lockup => self.str_field("assetSend")?,
+            Side::Claim => self.str_field("assetReceive")?,
+        };
+        let script = if asset == "L-BTC" {
+            SwapScript::Liquid(LBtcSwapScript {
+                script: self.liquid_script(side, our_pubkey)?,
+                swap_id,
+            })
+        } else {
+            SwapScript::Btc(BtcSwapScript {
+                script: self.btc_script(side, our_pubkey)?,
+                swap_id,
+            })
+        };
+
+        Ok((script, private_key))
+    }
+
+    pub(crate) fn to_value(&self) -> &Value {
+        &self.data
+    }
+
+    // Timeout and address of the output we lock up, which is the one that
+    // has to be refunded; reverse swaps use the lockup of Boltz
+    pub(crate) fn timeout_block_height(&self) -> Option<u32> {
+        match self.data["type"].as_str() {
+            Some("chain") => self.data["lockupDetails"]["timeoutBlockHeight"].as_u64(),
+            _ => self.data["timeoutBlockHeight"].as_u64(),
+        }
+        .map(|height| height as u32)
+    }
+
+    pub(crate) fn lockup_address(&self) -> Option<String> {
+        match self.data["type"].as_str() {
+            Some("submarine") => self.data["address"].as_str(),
+            Some("reverse") => self.data["lockupAddress"].as_str(),
+            _ => self.data["lockupDetails"]["lockupAddress"].as_str(),
+        }
+        .map(str::to_string)
+    }
+
+    // Submarine swaps and the lockup side of chain swaps are refunded, reverse
+    // swaps and the claim side of chain swaps are claimed
+    fn resolve_side(&self, side: Option<String>) -> PyResult<Side> {
+        match (self.str_field("type")?.as_str(), side) {
+            ("submarine", _) => Ok(Side::Lockup),
+            ("reverse", _) => Ok(Side::Claim),
+            (_, Some(side)) => parse_side(side.as_str()),
+            (_, None) => Ok(Side::Lockup),
+        }
+    }
+
+    fn key_for_side(&self, side: &Side, deriver: Option<&SwapKeyDeriver>) -> PyResult<Vec<u8>> {
+        let name = match side {
+            Side::Lockup => "refund",
+            Side::Claim => "claim",
+        };
+
+        if let Some(private_key) = self.data[format!("{}PrivateKey", name)].as_str() {
+            return handle_rust_error("could not decode private key", hex::decode(private_key));
+        }
+        match (self.data[format!("{}PrivateKeyIndex", name)].as_u64(), deriver) {
+            (Some(index), Some(deriver)) => {
+                let keys = deriver.keypair(index as u32)?;
+                Ok(keys.secret_key().as_ref().to_vec())
+            }
+            (Some(_), None) => Err(PyValueError::new_err("a SwapKeyDeriver is required for derived keys")),
+            (None, _) => Err(new_python_error::<BoltzError>(&format!("{}: missing {} key", COULD_NOT_PARSE_RESCUE_FILE, name))),
+        }
+    }
+
+    fn chain_details(&self, side: &Side) -> PyResult<boltz::ChainSwapDetails> {
+        match side {
+            Side::Lockup => parse_field(&self.data["lockupDetails"]),
+            Side::Claim => parse_field(&self.data["claimDetails"]),
+        }
+    }
+
+    fn btc_script(&self, side: Side, our_pubkey: PublicKey) -> PyResult<boltz_client::BtcSwapScript> {
+        let script = match self.str_field("type")?.as_str() {
+            "submarine" => boltz_client::BtcSwapScript::submarine_from_swap_resp(&parse_field(&self.data)?, our_pubkey),
+            "reverse" => boltz_client::BtcSwapScript::reverse_from_swap_resp(&parse_field(&self.data)?, our_pubkey),
+            _ => boltz_client::BtcSwapScript::chain_from_swap_resp(side.clone(), self.chain_details(&side)?, our_pubkey),
+        };
+
+        handle_boltz_error::<ScriptError, _>(COULD_NOT_REBUILD_SCRIPT, script)
+    }
+
+    fn liquid_script(&self, side: Side, our_pubkey: PublicKey) -> PyResult<boltz_client::LBtcSwapScript> {
+        let script = match self.str_field("type")?.as_str() {
+            "submarine" => boltz_client::LBtcSwapScript::submarine_from_swap_resp(&parse_field(&self.data)?, our_pubkey),
+            "reverse" => boltz_client::LBtcSwapScript::reverse_from_swap_resp(&parse_field(&self.data)?, our_pubkey),
+            _ => boltz_client::LBtcSwapScript::chain_from_swap_resp(side.clone(), self.chain_details(&side)?, our_pubkey),
+        };
+
+        handle_boltz_error::<ScriptError, _>(COULD_NOT_REBUILD_SCRIPT, script)
+    }
+}
+
+#[pymethods]
+impl RescueSwap {
+    #[staticmethod]
+    #[pyo3(signature = (response, asset_send, asset_receive, key_index=None, private_key=None, invoice=None))]
+    pub fn from_submarine(
+        response: CreateSubmarineResponse,
+        asset_send: String,
+        asset_receive: String,
+        key_index: Option<u32>,
+        private_key: Option<Vec<u8>>,
+        invoice: Option<String>,
+    ) -> PyResult<Self> {
+        check_asset(asset_send.as_str())?;
+        check_asset(asset_receive.as_str())?;
+
+        let mut data = json!({
+            "type": "submarine",
+            "id": response.id,
+            "assetSend": asset_send,
+            "assetReceive": asset_receive,
+            "address": response.address,
+            "bip21": response.bip21,
+            "expectedAmount": response.expected_amount,
+            "acceptZeroConf": response.accept_zero_conf,
+            "claimPublicKey": hex::encode(&response.claim_public_key),
+            "swapTree": tree_json(&response.swap_tree),
+            "timeoutBlockHeight": response.timeout_block_height,
+            "blindingKey": response.blinding_key,
+            "invoice": invoice,
+        });
+        set_key(&mut data, "refund", key_index, private_key);
+
+        Ok(RescueSwap { data })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (response, asset_send, asset_receive, preimage=None, key_index=None, private_key=None))]
+    pub fn from_reverse(
+        response: CreateReverseResponse,
+        asset_send: String,
+        asset_receive: String,
+        preimage: Option<String>,
+        key_index: Option<u32>,
+        private_key: Option<Vec<u8>>,
+    ) -> PyResult<Self> {
+        check_asset(asset_send.as_str())?;
+        check_asset(asset_receive.as_str())?;
+
+        let mut data = json!({
+            "type": "reverse",
+            "id": response.id,
+            "assetSend": asset_send,
+            "assetReceive": asset_receive,
+            "invoice": response.invoice,
+            "lockupAddress": response.lockup_address,
+            "refundPublicKey": hex::encode(&response.refund_public_key),
+            "swapTree": tree_json(&response.swap_tree),
+            "timeoutBlockHeight": response.timeout_block_height,
+            "onchainAmount": response.onchain_amount,
+            "blindingKey": response.blinding_key,
+            "preimage": preimage,
+        });
+        set_key(&mut data, "claim", key_index, private_key);
+
+        Ok(RescueSwap { data })
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (
+        response,
+        asset_send,
+        asset_receive,
+        preimage=None,
+        claim_key_index=None,
+        refund_key_index=None,
+        claim_private_key=None,
+        refund_private_key=None,
+    ))]
+    pub fn from_chain(
+        response: CreateChainResponse,
+        asset_send: String,
+        asset_receive: String,
+        preimage: Option<String>,
+        claim_key_index: Option<u32>,
+        refund_key_index: Option<u32>,
+        claim_private_key: Option<Vec<u8>>,
+        refund_private_key: Option<Vec<u8>>,
+    ) -> PyResult<Self> {
+        check_asset(asset_send.as_str())?;
+        check_asset(asset_receive.as_str())?;
+
+        let mut data = json!({
+            "type": "chain",
+            "id": response.id,
+            "assetSend": asset_send,
+            "assetReceive": asset_receive,
+            "lockupDetails": details_json(&response.lockup_details),
+            "claimDetails": details_json(&response.claim_details),
+            "preimage": preimage,
+        });
+        set_key(&mut data, "claim", claim_key_index, claim_private_key);
+        set_key(&mut data, "refund", refund_key_index, refund_private_key);
+
+        Ok(RescueSwap { data })
+    }
+
+    #[getter]
+    pub fn id(&self) -> PyResult<String> {
+        self.str_field("id")
+    }
+
+    #[getter]
+    pub fn swap_type(&self) -> PyResult<String> {
+        self.str_field("type")
+    }
+
+    #[getter]
+    pub fn asset_send(&self) -> PyResult<String> {
+        self.str_field("assetSend")
+    }
+
+    #[getter]
+    pub fn asset_receive(&self) -> PyResult<String> {
+        self.str_field("assetReceive")
+    }
+
+    #[getter]
+    pub fn preimage(&self) -> Option<String> {
+        self.data["preimage"].as_str().map(str::to_string)
+    }
+
+    #[pyo3(signature = (deriver=None, side=None))]
+    pub fn private_key(&self, deriver: Option<SwapKeyDeriver>, side: Option<String>) -> PyResult<Vec<u8>> {
+        let side = self.resolve_side(side)?;
+        self.key_for_side(&side, deriver.as_ref())
+    }
+
+    /// Rebuilds the swap script of the side we spend from; BtcSwapScript or
+    /// LBtcSwapScript depending on the asset of that side.
+    #[pyo3(signature = (deriver=None, side=None))]
+    pub fn script(&self, py: Python, deriver: Option<SwapKeyDeriver>, side: Option<String>) -> PyResult<PyObject> {
+        let side = self.resolve_side(side)?;
+        match self.swap_script(deriver.as_ref(), side)?.0 {
+            SwapScript::Btc(script) => Ok(Py::new(py, script)?.into_py(py)),
+            SwapScript::Liquid(script) => Ok(Py::new(py, script)?.into_py(py)),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct RescueFile {
+    #[pyo3(get)]
+    pub swaps: Vec<RescueSwap>,
+    #[pyo3(get)]
+    pub deriver: Option<SwapKeyDeriver>,
+}
+
+#[pymethods]
+impl RescueFile {
+    #[new]
+    #[pyo3(signature = (swaps, deriver=None))]
+    pub fn new(swaps: Vec<RescueSwap>, deriver: Option<SwapKeyDeriver>) -> Self {
+        RescueFile { swaps, deriver }
+    }
+
+    // The web app only stores the mnemonic, the swaps are listed as well so
+    // they can be recovered without querying Boltz. Keys of a mnemonic with a
+    // passphrase are exported as xprv, since the passphrase is not written;
+    // the web app can not import such files.
+    pub fn to_json(&self) -> PyResult<String> {
+        let mut file = json!({ "swaps": self.swaps.iter().map(|swap| swap.data.clone()).collect::<Vec<_>>() });
+        if let Some(deriver) = &self.deriver {
+            match deriver.mnemonic() {
+                Some(mnemonic) if !deriver.has_passphrase() => file["mnemonic"] = json!(mnemonic),
+                _ => file["xprv"] = json!(deriver.xprv()),
+            }
+        }
+
+        handle_rust_error("could not serialize rescue file", serde_json::to_string_pretty(&file))
+    }
+
+    /// Accepts rescue files with a mnemonic and swaps, a list of swaps or the
+    /// refund file of a single swap. The swaps of rescue files of the web app,
+    /// which only contain the mnemonic, are restored from Boltz by the xpub.
+    #[staticmethod]
+    #[pyo3(signature = (data, client=None))]
+    pub fn from_json(py: Python, data: String, client: Option<PyRef<Client>>) -> PyResult<Self> {
+        let file: Value = handle_rust_error(COULD_NOT_PARSE_RESCUE_FILE, serde_json::from_str(data.as_str()))?;
+
+        let deriver = match (file["mnemonic"].as_str(), file["xprv"].as_str()) {
+            (Some(mnemonic), _) => Some(SwapKeyDeriver::from_mnemonic(mnemonic.to_string(), String::new())?),
+            (None, Some(xprv)) => Some(SwapKeyDeriver::from_xprv(xprv.to_string())?),
+            (None, None) => None,
+        };
+        let swaps = match &file {
+            Value::Array(swaps) => swaps.clone(),
+            Value::Object(_) if file.get("type").is_some() => vec![file.clone()],
+            Value::Object(_) => match file["swaps"].as_array() {
+                Some(swaps) => swaps.clone(),
+                None => match (&deriver, client) {
+                    (Some(deriver), Some(client)) => {
+                        let xpub = deriver.xpub()?;
+                        let api = client.boltz();
+                        let restored = py.allow_threads(|| api.restore_swaps(xpub))?;
+                        return Ok(RescueFile {
+                            swaps: restored
+                                .iter()
+                                .map(|swap| restored_swap(swap, deriver))
+                                .collect::<PyResult<_>>()?,
+                            deriver: Some(deriver.clone()),
+                        });
+                    }
+                    (Some(_), None) => {
+                        return Err(PyValueError::new_err("a Client is required to restore the swaps of the rescue file"))
+                    }
+                    (None, _) => Vec::new(),
+                },
+            },
+            _ => return Err(new_python_error::<BoltzError>(COULD_NOT_PARSE_RESCUE_FILE)),
+        };
+
+        Ok(RescueFile {
+            swaps: swaps.into_iter().map(RescueSwap::from_value).collect::<PyResult<_>>()?,
+            deriver,
+        })
+    }
+}
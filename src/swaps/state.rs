@@ -0,0 +1,284 @@
+use boltz_client::boltz::SwapType;
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, PyErr, PyResult};
+
+use crate::types::status::SwapStatus;
+use crate::utils::errors::{new_python_error, BoltzError};
+
+pub(crate) fn parse_swap_type(swap_type: &str) -> Result<SwapType, PyErr> {
+    match swap_type {
+        "submarine" => Ok(SwapType::Submarine),
+        "reverse" => Ok(SwapType::ReverseSubmarine),
+        "chain" => Ok(SwapType::Chain),
+        _ => Err(PyValueError::new_err("invalid swap type")),
+    }
+}
+
+pub(crate) fn swap_type_str(swap_type: &SwapType) -> &'static str {
+    match swap_type {
+        SwapType::Submarine => "submarine",
+        SwapType::ReverseSubmarine => "reverse",
+        SwapType::Chain => "chain",
+    }
+}
+
+// Updates can be skipped when we are not subscribed the whole time, so every
+// status that can follow later in the lifecycle of a swap is a legal successor.
+pub fn is_legal_transition(swap_type: &SwapType, from: SwapStatus, to: SwapStatus) -> bool {
+    use SwapStatus::*;
+
+    if from == to {
+        return true;
+    }
+
+    match swap_type {
+        SwapType::Submarine => match from {
+            SwapCreated => matches!(
+                to,
+                InvoiceSet
+                    | TransactionMempool
+                    | TransactionZeroconfRejected
+                    | TransactionConfirmed
+                    | InvoicePending
+                    | InvoicePaid
+                    | InvoiceFailedToPay
+                    | TransactionClaimPending
+                    | TransactionClaimed
+                    | TransactionLockupFailed
+                    | SwapExpired
+            ),
+            InvoiceSet => matches!(
+                to,
+                TransactionMempool
+                    | TransactionZeroconfRejected
+                    | TransactionConfirmed
+                    | InvoicePending
+                    | InvoicePaid
+                    | InvoiceFailedToPay
+                    | TransactionClaimPending
+                    | TransactionClaimed
+                    | TransactionLockupFailed
+                    | SwapExpired
+            ),
+            TransactionMempool => matches!(
+                to,
+                TransactionZeroconfRejected
+                    | TransactionConfirmed
+                    | InvoicePending
+                    | InvoicePaid
+                    | InvoiceFailedToPay
+                    | TransactionClaimPending
+                    | TransactionClaimed
+                    | TransactionLockupFailed
+                    | SwapExpired
+            ),
+            TransactionZeroconfRejected => matches!(
+                to,
+                TransactionConfirmed
+                    | InvoicePending
+                    | InvoicePaid
+                    | InvoiceFailedToPay
+                    | TransactionClaimPending
+                    | TransactionClaimed
+                    | TransactionLockupFailed
+                    | SwapExpired
+            ),
+            TransactionConfirmed => matches!(
+                to,
+                InvoicePending
+                    | InvoicePaid
+                    | InvoiceFailedToPay
+                    | TransactionClaimPending
+                    | TransactionClaimed
+                    | TransactionLockupFailed
+                    | SwapExpired
+            ),
+            InvoicePending => matches!(
+                to,
+                InvoicePaid | InvoiceFailedToPay | TransactionClaimPending | TransactionClaimed
+            ),
+            InvoicePaid => matches!(to, TransactionClaimPending | TransactionClaimed),
+            TransactionClaimPending => matches!(to, TransactionClaimed),
+            TransactionLockupFailed => matches!(to, SwapExpired),
+            _ => false,
+        },
+        SwapType::ReverseSubmarine => match from {
+            SwapCreated => matches!(
+                to,
+                MinerFeePaid
+                    | TransactionMempool
+                    | TransactionConfirmed
+                    | InvoiceSettled
+                    | TransactionDirect
+                    | InvoiceExpired
+                    | TransactionFailed
+                    | TransactionRefunded
+                    | SwapExpired
+            ),
+            MinerFeePaid => matches!(
+                to,
+                TransactionMempool
+                    | TransactionConfirmed
+                    | InvoiceSettled
+                    | TransactionDirect
+                    | InvoiceExpired
+                    | TransactionFailed
+                    | TransactionRefunded
+                    | SwapExpired
+            ),
+            TransactionMempool => matches!(
+                to,
+                TransactionConfirmed | InvoiceSettled | TransactionFailed | TransactionRefunded | SwapExpired
+            ),
+            TransactionConfirmed => matches!(to, InvoiceSettled | TransactionRefunded | SwapExpired),
+            SwapExpired => matches!(to, TransactionRefunded),
+            _ => false,
+        },
+        SwapType::Chain => match from {
+            SwapCreated => matches!(
+                to,
+                TransactionMempool
+                    | TransactionZeroconfRejected
+                    | TransactionConfirmed
+                    | TransactionServerMempool
+                    | TransactionServerConfirmed
+                    | TransactionClaimPending
+                    | TransactionClaimed
+                    | TransactionLockupFailed
+                    | TransactionFailed
+                    | TransactionRefunded
+                    | SwapExpired
+            ),
+            TransactionMempool => matches!(
+                to,
+                TransactionZeroconfRejected
+                    | TransactionConfirmed
+                    | TransactionServerMempool
+                    | TransactionServerConfirmed
+                    | TransactionClaimPending
+                    | TransactionClaimed
+                    | TransactionLockupFailed
+                    | TransactionFailed
+                    | TransactionRefunded
+                    | SwapExpired
+            ),
+            TransactionZeroconfRejected => matches!(
+                to,
+                TransactionConfirmed
+                    | TransactionServerMempool
+                    | TransactionServerConfirmed
+                    | TransactionClaimPending
+                    | TransactionClaimed
+                    | TransactionLockupFailed
+                    | TransactionFailed
+                    | TransactionRefunded
+                    | SwapExpired
+            ),
+            TransactionConfirmed => matches!(
+                to,
+                TransactionServerMempool
+                    | TransactionServerConfirmed
+                    | TransactionClaimPending
+                    | TransactionClaimed
+                    | TransactionLockupFailed
+                    | TransactionFailed
+                    | TransactionRefunded
+                    | SwapExpired
+            ),
+            TransactionServerMempool => matches!(
+                to,
+                TransactionServerConfirmed
+                    | TransactionClaimPending
+                    | TransactionClaimed
+                    | TransactionRefunded
+                    | SwapExpired
+            ),
+            TransactionServerConfirmed => matches!(
+                to,
+                TransactionClaimPending | TransactionClaimed | TransactionRefunded | SwapExpired
+            ),
+            TransactionClaimPending => matches!(to, TransactionClaimed),
+            TransactionLockupFailed | TransactionFailed => matches!(to, TransactionRefunded | SwapExpired),
+            SwapExpired => matches!(to, TransactionRefunded),
+            _ => false,
+        },
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct SwapStateMachine {
+    swap_type: SwapType,
+    #[pyo3(get)]
+    status: SwapStatus,
+    #[pyo3(get)]
+    history: Vec<SwapStatus>,
+}
+
+#[pymethods]
+impl SwapStateMachine {
+    #[new]
+    pub fn new(swap_type: String, status: Option<SwapStatus>) -> PyResult<Self> {
+        let status = status.unwrap_or(SwapStatus::SwapCreated);
+        Ok(SwapStateMachine {
+            swap_type: parse_swap_type(swap_type.as_str())?,
+            status,
+            history: vec![status],
+        })
+    }
+
+    #[getter]
+    pub fn swap_type(&self) -> &'static str {
+        swap_type_str(&self.swap_type)
+    }
+
+    pub fn can_transition(&self, status: SwapStatus) -> bool {
+        is_legal_transition(&self.swap_type, self.status, status)
+    }
+
+    pub fn transition(&mut self, status: SwapStatus) -> PyResult<()> {
+        if !self.can_transition(status) {
+            return Err(new_python_error::<BoltzError>(&format!(
+                "illegal transition of {} swap from {} to {}",
+                self.swap_type(),
+                self.status.as_str(),
+                status.as_str()
+            )));
+        }
+        if status != self.status {
+            self.status = status;
+            self.history.push(status);
+        }
+
+        Ok(())
+    }
+
+    pub fn is_final(&self) -> bool {
+        self.status.is_final()
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.status.is_failed()
+    }
+
+    pub fn can_claim(&self) -> bool {
+        self.status.is_claimable(&self.swap_type)
+    }
+
+    pub fn requires_refund(&self) -> bool {
+        let locked = self.history.iter().any(|status| {
+            matches!(
+                status,
+                SwapStatus::TransactionMempool
+                    | SwapStatus::TransactionZeroconfRejected
+                    | SwapStatus::TransactionConfirmed
+                    | SwapStatus::TransactionLockupFailed
+            )
+        });
+
+        match self.swap_type {
+            SwapType::ReverseSubmarine => false,
+            _ => locked && self.status.requires_refund(),
+        }
+    }
+}
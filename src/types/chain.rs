@@ -0,0 +1,156 @@
+use pyo3::{pyclass, pymethods, PyErr, Python, Bound, PyResult};
+use pyo3::prelude::PyDictMethods;
+use pyo3::types::PyDict;
+
+use crate::types::submarine::SwapTree;
+use crate::utils::keys::parse_public_key;
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct ChainSwapDetails {
+    #[pyo3(get)]
+    pub swap_tree: SwapTree,
+    #[pyo3(get)]
+    pub lockup_address: String,
+    #[pyo3(get)]
+    pub server_public_key: Vec<u8>,
+    #[pyo3(get)]
+    pub timeout_block_height: u32,
+    #[pyo3(get)]
+    pub amount: u64,
+    #[pyo3(get)]
+    pub blinding_key: Option<String>,
+    #[pyo3(get)]
+    pub refund_address: Option<String>,
+    #[pyo3(get)]
+    pub claim_address: Option<String>,
+    #[pyo3(get)]
+    pub bip21: Option<String>,
+}
+
+#[pymethods]
+impl ChainSwapDetails {
+    #[new]
+    pub fn new(
+        swap_tree: SwapTree,
+        lockup_address: String,
+        server_public_key: Vec<u8>,
+        timeout_block_height: u32,
+        amount: u64,
+        blinding_key: Option<String>,
+        refund_address: Option<String>,
+        claim_address: Option<String>,
+        bip21: Option<String>,
+    ) -> Self {
+        ChainSwapDetails {
+            swap_tree,
+            lockup_address,
+            server_public_key,
+            timeout_block_height,
+            amount,
+            blinding_key,
+            refund_address,
+            claim_address,
+            bip21,
+        }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("swap_tree", self.swap_tree.to_dict(py)?)?;
+        dict.set_item("lockup_address", self.lockup_address.clone())?;
+        dict.set_item("server_public_key", self.server_public_key.clone())?;
+        dict.set_item("timeout_block_height", self.timeout_block_height)?;
+        dict.set_item("amount", self.amount)?;
+        dict.set_item("blinding_key", self.blinding_key.clone())?;
+        dict.set_item("refund_address", self.refund_address.clone())?;
+        dict.set_item("claim_address", self.claim_address.clone())?;
+        dict.set_item("bip21", self.bip21.clone())?;
+        Ok(dict)
+    }
+}
+
+impl TryFrom<ChainSwapDetails> for boltz_client::swaps::boltz::ChainSwapDetails {
+    type Error = PyErr;
+
+    fn try_from(value: ChainSwapDetails) -> Result<Self, Self::Error> {
+        Ok(boltz_client::swaps::boltz::ChainSwapDetails {
+            swap_tree: value.swap_tree.into(),
+            lockup_address: value.lockup_address,
+            server_public_key: parse_public_key(value.server_public_key)?,
+            timeout_block_height: value.timeout_block_height,
+            amount: value.amount,
+            blinding_key: value.blinding_key,
+            refund_address: value.refund_address,
+            claim_address: value.claim_address,
+            bip21: value.bip21,
+        })
+    }
+}
+
+impl From<boltz_client::swaps::boltz::ChainSwapDetails> for ChainSwapDetails {
+    fn from(value: boltz_client::swaps::boltz::ChainSwapDetails) -> Self {
+        ChainSwapDetails {
+            swap_tree: value.swap_tree.into(),
+            lockup_address: value.lockup_address,
+            server_public_key: value.server_public_key.to_bytes(),
+            timeout_block_height: value.timeout_block_height,
+            amount: value.amount,
+            blinding_key: value.blinding_key,
+            refund_address: value.refund_address,
+            claim_address: value.claim_address,
+            bip21: value.bip21,
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct CreateChainResponse {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub claim_details: ChainSwapDetails,
+    #[pyo3(get)]
+    pub lockup_details: ChainSwapDetails,
+}
+
+#[pymethods]
+impl CreateChainResponse {
+    #[new]
+    pub fn new(id: String, claim_details: ChainSwapDetails, lockup_details: ChainSwapDetails) -> Self {
+        CreateChainResponse {
+            id,
+            claim_details,
+            lockup_details,
+        }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("id", self.id.clone())?;
+        dict.set_item("claim_details", self.claim_details.to_dict(py)?)?;
+        dict.set_item("lockup_details", self.lockup_details.to_dict(py)?)?;
+        Ok(dict)
+    }
+}
+
+impl TryFrom<CreateChainResponse> for boltz_client::swaps::boltz::CreateChainResponse {
+    type Error = PyErr;
+
+    fn try_from(value: CreateChainResponse) -> Result<Self, Self::Error> {
+        Ok(boltz_client::swaps::boltz::CreateChainResponse {
+            id: value.id,
+            claim_details: value.claim_details.try_into()?,
+            lockup_details: value.lockup_details.try_into()?,
+        })
+    }
+}
+
+impl From<boltz_client::swaps::boltz::CreateChainResponse> for CreateChainResponse {
+    fn from(value: boltz_client::swaps::boltz::CreateChainResponse) -> Self {
+        CreateChainResponse {
+            id: value.id,
+            claim_details: value.claim_details.into(),
+            lockup_details: value.lockup_details.into(),
+        }
+    }
+}
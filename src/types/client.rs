@@ -484,3 +484,203 @@ impl From<boltz_client::swaps::boltz::HeightResponse> for HeightResponse {
         }
     }
 }
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct ChainMinerFees {
+    #[pyo3(get)]
+    pub server: u64,
+    #[pyo3(get)]
+    pub user: PairMinerFees,
+}
+
+#[pymethods]
+impl ChainMinerFees {
+    #[new]
+    pub fn new(server: u64, user: PairMinerFees) -> Self {
+        ChainMinerFees { server, user }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("server", self.server)?;
+        dict.set_item("user", self.user.to_dict(py)?)?;
+        Ok(dict)
+    }
+}
+
+impl From<boltz_client::swaps::boltz::ChainMinerFees> for ChainMinerFees {
+    fn from(value: boltz_client::swaps::boltz::ChainMinerFees) -> Self {
+        ChainMinerFees {
+            server: value.server,
+            user: value.user.into(),
+        }
+    }
+}
+
+impl From<ChainMinerFees> for boltz_client::swaps::boltz::ChainMinerFees {
+    fn from(value: ChainMinerFees) -> Self {
+        boltz_client::swaps::boltz::ChainMinerFees {
+            server: value.server,
+            user: value.user.into(),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct ChainFees {
+    #[pyo3(get)]
+    pub percentage: f64,
+    #[pyo3(get)]
+    pub miner_fees: ChainMinerFees,
+}
+
+#[pymethods]
+impl ChainFees {
+    #[new]
+    pub fn new(percentage: f64, miner_fees: ChainMinerFees) -> Self {
+        ChainFees {
+            percentage,
+            miner_fees,
+        }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("percentage", self.percentage)?;
+        dict.set_item("miner_fees", self.miner_fees.to_dict(py)?)?;
+        Ok(dict)
+    }
+}
+
+impl From<boltz_client::swaps::boltz::ChainFees> for ChainFees {
+    fn from(value: boltz_client::swaps::boltz::ChainFees) -> Self {
+        ChainFees {
+            percentage: value.percentage,
+            miner_fees: value.miner_fees.into(),
+        }
+    }
+}
+
+impl From<ChainFees> for boltz_client::swaps::boltz::ChainFees {
+    fn from(value: ChainFees) -> Self {
+        boltz_client::swaps::boltz::ChainFees {
+            percentage: value.percentage,
+            miner_fees: value.miner_fees.into(),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct ChainPair {
+    #[pyo3(get)]
+    pub hash: String,
+    #[pyo3(get)]
+    pub rate: f64,
+    #[pyo3(get)]
+    pub limits: PairLimits,
+    #[pyo3(get)]
+    pub fees: ChainFees,
+}
+
+#[pymethods]
+impl ChainPair {
+    #[new]
+    pub fn new(hash: String, rate: f64, limits: PairLimits, fees: ChainFees) -> Self {
+        ChainPair {
+            hash,
+            rate,
+            limits,
+            fees,
+        }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("hash", self.hash.clone())?;
+        dict.set_item("rate", self.rate)?;
+        dict.set_item("limits", self.limits.to_dict(py)?)?;
+        dict.set_item("fees", self.fees.to_dict(py)?)?;
+        Ok(dict)
+    }
+}
+
+impl From<boltz_client::swaps::boltz::ChainPair> for ChainPair {
+    fn from(value: boltz_client::swaps::boltz::ChainPair) -> Self {
+        ChainPair {
+            hash: value.hash,
+            rate: value.rate,
+            limits: value.limits.into(),
+            fees: value.fees.into(),
+        }
+    }
+}
+
+impl From<ChainPair> for boltz_client::swaps::boltz::ChainPair {
+    fn from(value: ChainPair) -> Self {
+        boltz_client::swaps::boltz::ChainPair {
+            hash: value.hash,
+            rate: value.rate,
+            limits: value.limits.into(),
+            fees: value.fees.into(),
+        }
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct GetChainPairsResponse {
+    #[pyo3(get)]
+    pub btc: HashMap<String, ChainPair>,
+    #[pyo3(get)]
+    pub lbtc: HashMap<String, ChainPair>,
+}
+
+#[pymethods]
+impl GetChainPairsResponse {
+    #[new]
+    pub fn new(btc: HashMap<String, ChainPair>, lbtc: HashMap<String, ChainPair>) -> Self {
+        GetChainPairsResponse { btc, lbtc }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        let btc = PyDict::new_bound(py);
+        for (key, value) in &self.btc {
+            btc.set_item(key, value.to_dict(py)?)?;
+        }
+        let lbtc = PyDict::new_bound(py);
+        for (key, value) in &self.lbtc {
+            lbtc.set_item(key, value.to_dict(py)?)?;
+        }
+        dict.set_item("btc", btc)?;
+        dict.set_item("lbtc", lbtc)?;
+        Ok(dict)
+    }
+}
+
+impl From<boltz_client::swaps::boltz::GetChainPairsResponse> for GetChainPairsResponse {
+    fn from(value: boltz_client::swaps::boltz::GetChainPairsResponse) -> Self {
+        let mut btc = HashMap::new();
+        for (key, value) in value.btc {
+            btc.insert(key, value.into());
+        }
+        let mut lbtc = HashMap::new();
+        for (key, value) in value.lbtc {
+            lbtc.insert(key, value.into());
+        }
+        GetChainPairsResponse { btc, lbtc }
+    }
+}
+
+impl From<GetChainPairsResponse> for boltz_client::swaps::boltz::GetChainPairsResponse {
+    fn from(value: GetChainPairsResponse) -> Self {
+        let mut btc = HashMap::new();
+        for (key, value) in value.btc {
+            btc.insert(key, value.into());
+        }
+        let mut lbtc = HashMap::new();
+        for (key, value) in value.lbtc {
+            lbtc.insert(key, value.into());
+        }
+        boltz_client::swaps::boltz::GetChainPairsResponse { btc, lbtc }
+    }
+}
@@ -1,2 +1,7 @@
+pub mod chain;
 pub mod client;
+pub mod onchain;
+pub mod reverse;
+pub mod status;
 pub mod submarine;
+pub mod transaction;
@@ -0,0 +1,60 @@
+use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
+use pyo3::prelude::PyDictMethods;
+use pyo3::types::PyDict;
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Utxo {
+    #[pyo3(get)]
+    pub txid: String,
+    #[pyo3(get)]
+    pub vout: u32,
+    #[pyo3(get)]
+    pub value: Option<u64>,
+    #[pyo3(get)]
+    pub height: Option<u32>,
+}
+
+#[pymethods]
+impl Utxo {
+    #[new]
+    pub fn new(txid: String, vout: u32, value: Option<u64>, height: Option<u32>) -> Self {
+        Utxo {
+            txid,
+            vout,
+            value,
+            height,
+        }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("txid", self.txid.clone())?;
+        dict.set_item("vout", self.vout)?;
+        dict.set_item("value", self.value)?;
+        dict.set_item("height", self.height)?;
+        Ok(dict)
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct ScriptHistoryItem {
+    #[pyo3(get)]
+    pub txid: String,
+    #[pyo3(get)]
+    pub height: Option<u32>,
+}
+
+#[pymethods]
+impl ScriptHistoryItem {
+    #[new]
+    pub fn new(txid: String, height: Option<u32>) -> Self {
+        ScriptHistoryItem { txid, height }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("txid", self.txid.clone())?;
+        dict.set_item("height", self.height)?;
+        Ok(dict)
+    }
+}
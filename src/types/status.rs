@@ -0,0 +1,244 @@
+use boltz_client::boltz::SwapType;
+use pyo3::prelude::PyDictMethods;
+use pyo3::types::PyDict;
+use pyo3::{pyclass, pymethods, Bound, PyResult, Python};
+use serde::Deserialize;
+
+use crate::swaps::state::parse_swap_type;
+
+#[pyclass]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwapStatus {
+    SwapCreated,
+    SwapExpired,
+    InvoiceSet,
+    InvoicePending,
+    InvoicePaid,
+    InvoiceFailedToPay,
+    InvoiceSettled,
+    InvoiceExpired,
+    MinerFeePaid,
+    TransactionMempool,
+    TransactionZeroconfRejected,
+    TransactionConfirmed,
+    TransactionDirect,
+    TransactionServerMempool,
+    TransactionServerConfirmed,
+    TransactionClaimPending,
+    TransactionClaimed,
+    TransactionLockupFailed,
+    TransactionFailed,
+    TransactionRefunded,
+    // Status strings this version does not know yet, e.g. ones Boltz added
+    // later. They are tolerated so that one update cannot break a stream.
+    Unknown,
+}
+
+impl SwapStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapStatus::SwapCreated => "swap.created",
+            SwapStatus::SwapExpired => "swap.expired",
+            SwapStatus::InvoiceSet => "invoice.set",
+            SwapStatus::InvoicePending => "invoice.pending",
+            SwapStatus::InvoicePaid => "invoice.paid",
+            SwapStatus::InvoiceFailedToPay => "invoice.failedToPay",
+            SwapStatus::InvoiceSettled => "invoice.settled",
+            SwapStatus::InvoiceExpired => "invoice.expired",
+            SwapStatus::MinerFeePaid => "minerfee.paid",
+            SwapStatus::TransactionMempool => "transaction.mempool",
+            SwapStatus::TransactionZeroconfRejected => "transaction.zeroconf.rejected",
+            SwapStatus::TransactionConfirmed => "transaction.confirmed",
+            SwapStatus::TransactionDirect => "transaction.direct",
+            SwapStatus::TransactionServerMempool => "transaction.server.mempool",
+            SwapStatus::TransactionServerConfirmed => "transaction.server.confirmed",
+            SwapStatus::TransactionClaimPending => "transaction.claim.pending",
+            SwapStatus::TransactionClaimed => "transaction.claimed",
+            SwapStatus::TransactionLockupFailed => "transaction.lockupFailed",
+            SwapStatus::TransactionFailed => "transaction.failed",
+            SwapStatus::TransactionRefunded => "transaction.refunded",
+            SwapStatus::Unknown => "unknown",
+        }
+    }
+
+    // Which lockup can be claimed depends on the swap type: Boltz claims the
+    // lockup of submarine swaps, the user the one of Boltz otherwise
+    pub(crate) fn is_claimable(&self, swap_type: &SwapType) -> bool {
+        match swap_type {
+            SwapType::Submarine => *self == SwapStatus::TransactionClaimPending,
+            SwapType::ReverseSubmarine => matches!(
+                self,
+                SwapStatus::TransactionMempool | SwapStatus::TransactionConfirmed
+            ),
+            SwapType::Chain => matches!(
+                self,
+                SwapStatus::TransactionServerMempool | SwapStatus::TransactionServerConfirmed
+            ),
+        }
+    }
+}
+
+impl From<&str> for SwapStatus {
+    fn from(s: &str) -> Self {
+        match s {
+            "swap.created" => SwapStatus::SwapCreated,
+            "swap.expired" => SwapStatus::SwapExpired,
+            "invoice.set" => SwapStatus::InvoiceSet,
+            "invoice.pending" => SwapStatus::InvoicePending,
+            "invoice.paid" => SwapStatus::InvoicePaid,
+            "invoice.failedToPay" => SwapStatus::InvoiceFailedToPay,
+            "invoice.settled" => SwapStatus::InvoiceSettled,
+            "invoice.expired" => SwapStatus::InvoiceExpired,
+            "minerfee.paid" => SwapStatus::MinerFeePaid,
+            "transaction.mempool" => SwapStatus::TransactionMempool,
+            "transaction.zeroconf.rejected" => SwapStatus::TransactionZeroconfRejected,
+            "transaction.confirmed" => SwapStatus::TransactionConfirmed,
+            "transaction.direct" => SwapStatus::TransactionDirect,
+            "transaction.server.mempool" => SwapStatus::TransactionServerMempool,
+            "transaction.server.confirmed" => SwapStatus::TransactionServerConfirmed,
+            "transaction.claim.pending" => SwapStatus::TransactionClaimPending,
+            "transaction.claimed" => SwapStatus::TransactionClaimed,
+            "transaction.lockupFailed" => SwapStatus::TransactionLockupFailed,
+            "transaction.failed" => SwapStatus::TransactionFailed,
+            "transaction.refunded" => SwapStatus::TransactionRefunded,
+            _ => SwapStatus::Unknown,
+        }
+    }
+}
+
+#[pymethods]
+impl SwapStatus {
+    #[staticmethod]
+    #[pyo3(name = "from_str")]
+    pub fn parse(status: String) -> Self {
+        SwapStatus::from(status.as_str())
+    }
+
+    pub fn __str__(&self) -> &'static str {
+        self.as_str()
+    }
+
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            SwapStatus::SwapExpired
+                | SwapStatus::InvoiceFailedToPay
+                | SwapStatus::InvoiceSettled
+                | SwapStatus::InvoiceExpired
+                | SwapStatus::TransactionDirect
+                | SwapStatus::TransactionClaimed
+                | SwapStatus::TransactionLockupFailed
+                | SwapStatus::TransactionFailed
+                | SwapStatus::TransactionRefunded
+        )
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(
+            self,
+            SwapStatus::SwapExpired
+                | SwapStatus::InvoiceFailedToPay
+                | SwapStatus::InvoiceExpired
+                | SwapStatus::TransactionLockupFailed
+                | SwapStatus::TransactionFailed
+                | SwapStatus::TransactionRefunded
+        )
+    }
+
+    pub fn requires_refund(&self) -> bool {
+        matches!(
+            self,
+            SwapStatus::SwapExpired
+                | SwapStatus::InvoiceFailedToPay
+                | SwapStatus::TransactionLockupFailed
+                | SwapStatus::TransactionFailed
+        )
+    }
+
+    pub fn can_claim(&self, swap_type: String) -> PyResult<bool> {
+        Ok(self.is_claimable(&parse_swap_type(swap_type.as_str())?))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SwapTransactionRaw {
+    pub id: String,
+    pub hex: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapUpdateRaw {
+    #[serde(default)]
+    pub id: String,
+    pub status: String,
+    pub zero_conf_rejected: Option<bool>,
+    pub failure_reason: Option<String>,
+    pub transaction: Option<SwapTransactionRaw>,
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct SwapUpdate {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub status: SwapStatus,
+    #[pyo3(get)]
+    pub zero_conf_rejected: Option<bool>,
+    #[pyo3(get)]
+    pub failure_reason: Option<String>,
+    #[pyo3(get)]
+    pub transaction_id: Option<String>,
+    #[pyo3(get)]
+    pub transaction_hex: Option<String>,
+}
+
+#[pymethods]
+impl SwapUpdate {
+    #[new]
+    pub fn new(
+        id: String,
+        status: SwapStatus,
+        zero_conf_rejected: Option<bool>,
+        failure_reason: Option<String>,
+        transaction_id: Option<String>,
+        transaction_hex: Option<String>,
+    ) -> Self {
+        SwapUpdate {
+            id,
+            status,
+            zero_conf_rejected,
+            failure_reason,
+            transaction_id,
+            transaction_hex,
+        }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("id", self.id.clone())?;
+        dict.set_item("status", self.status.as_str())?;
+        dict.set_item("zero_conf_rejected", self.zero_conf_rejected)?;
+        dict.set_item("failure_reason", self.failure_reason.clone())?;
+        dict.set_item("transaction_id", self.transaction_id.clone())?;
+        dict.set_item("transaction_hex", self.transaction_hex.clone())?;
+        Ok(dict)
+    }
+}
+
+impl From<SwapUpdateRaw> for SwapUpdate {
+    fn from(value: SwapUpdateRaw) -> Self {
+        let (transaction_id, transaction_hex) = match value.transaction {
+            Some(tx) => (Some(tx.id), tx.hex),
+            None => (None, None),
+        };
+        SwapUpdate {
+            id: value.id,
+            status: SwapStatus::from(value.status.as_str()),
+            zero_conf_rejected: value.zero_conf_rejected,
+            failure_reason: value.failure_reason,
+            transaction_id,
+            transaction_hex,
+        }
+    }
+}
@@ -191,3 +191,48 @@ impl From<boltz_client::swaps::boltz::CreateSubmarineResponse> for CreateSubmari
         }
     }
 }
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct SubmarineClaimDetails {
+    #[pyo3(get)]
+    pub preimage: String,
+    #[pyo3(get)]
+    pub pub_nonce: String,
+    #[pyo3(get)]
+    pub public_key: Vec<u8>,
+    #[pyo3(get)]
+    pub transaction_hash: String,
+}
+
+#[pymethods]
+impl SubmarineClaimDetails {
+    #[new]
+    pub fn new(preimage: String, pub_nonce: String, public_key: Vec<u8>, transaction_hash: String) -> Self {
+        SubmarineClaimDetails {
+            preimage,
+            pub_nonce,
+            public_key,
+            transaction_hash,
+        }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("preimage", self.preimage.clone())?;
+        dict.set_item("pub_nonce", self.pub_nonce.clone())?;
+        dict.set_item("public_key", self.public_key.clone())?;
+        dict.set_item("transaction_hash", self.transaction_hash.clone())?;
+        Ok(dict)
+    }
+}
+
+impl From<boltz_client::swaps::boltz::SubmarineClaimTxResponse> for SubmarineClaimDetails {
+    fn from(value: boltz_client::swaps::boltz::SubmarineClaimTxResponse) -> Self {
+        SubmarineClaimDetails {
+            preimage: value.preimage,
+            pub_nonce: value.pub_nonce,
+            public_key: value.public_key.to_bytes(),
+            transaction_hash: value.transaction_hash,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use pyo3::{pyclass, pymethods, Bound, FromPyObject, PyRef, PyResult, Python};
+use pyo3::prelude::PyDictMethods;
+use pyo3::types::PyDict;
+use serde::Deserialize;
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct SwapTransaction {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub hex: String,
+    #[pyo3(get)]
+    pub timeout_block_height: Option<u32>,
+    #[pyo3(get)]
+    pub timeout_eta: Option<u64>,
+}
+
+#[pymethods]
+impl SwapTransaction {
+    #[new]
+    pub fn new(id: String, hex: String, timeout_block_height: Option<u32>, timeout_eta: Option<u64>) -> Self {
+        SwapTransaction {
+            id,
+            hex,
+            timeout_block_height,
+            timeout_eta,
+        }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("id", self.id.clone())?;
+        dict.set_item("hex", self.hex.clone())?;
+        dict.set_item("timeout_block_height", self.timeout_block_height)?;
+        dict.set_item("timeout_eta", self.timeout_eta)?;
+        Ok(dict)
+    }
+}
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct SubmarineTransaction {
+    #[pyo3(get)]
+    pub id: String,
+    #[pyo3(get)]
+    pub hex: String,
+    #[pyo3(get)]
+    pub timeout_block_height: Option<u32>,
+    #[pyo3(get)]
+    pub timeout_eta: Option<u64>,
+}
+
+#[pymethods]
+impl SubmarineTransaction {
+    #[new]
+    pub fn new(id: String, hex: String, timeout_block_height: Option<u32>, timeout_eta: Option<u64>) -> Self {
+        SubmarineTransaction {
+            id,
+            hex,
+            timeout_block_height,
+            timeout_eta,
+        }
+    }
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("id", self.id.clone())?;
+        dict.set_item("hex", self.hex.clone())?;
+        dict.set_item("timeout_block_height", self.timeout_block_height)?;
+        dict.set_item("timeout_eta", self.timeout_eta)?;
+        Ok(dict)
+    }
+}
+
+// Lockup transactions can be passed to the transaction builders as hex or as
+// fetched from the Boltz API
+#[derive(FromPyObject)]
+pub(crate) enum LockupTx<'py> {
+    Hex(String),
+    Swap(PyRef<'py, SwapTransaction>),
+    Submarine(PyRef<'py, SubmarineTransaction>),
+}
+
+impl LockupTx<'_> {
+    pub fn into_hex(self) -> String {
+        match self {
+            LockupTx::Hex(hex) => hex,
+            LockupTx::Swap(tx) => tx.hex.clone(),
+            LockupTx::Submarine(tx) => tx.hex.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct LockupTransactionResponse {
+    pub id: String,
+    pub hex: String,
+    pub timeout_block_height: Option<u32>,
+    pub timeout_eta: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ChainTransaction {
+    pub id: String,
+    pub hex: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChainTimeout {
+    pub block_height: u32,
+    pub eta: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ChainLockup {
+    pub transaction: ChainTransaction,
+    pub timeout: Option<ChainTimeout>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ChainTransactionsResponse {
+    pub server_lock: Option<ChainLockup>,
+}
+
+impl From<LockupTransactionResponse> for SwapTransaction {
+    fn from(value: LockupTransactionResponse) -> Self {
+        SwapTransaction {
+            id: value.id,
+            hex: value.hex,
+            timeout_block_height: value.timeout_block_height,
+            timeout_eta: value.timeout_eta,
+        }
+    }
+}
+
+impl From<LockupTransactionResponse> for SubmarineTransaction {
+    fn from(value: LockupTransactionResponse) -> Self {
+        SubmarineTransaction {
+            id: value.id,
+            hex: value.hex,
+            timeout_block_height: value.timeout_block_height,
+            timeout_eta: value.timeout_eta,
+        }
+    }
+}
+
+impl From<ChainLockup> for SwapTransaction {
+    fn from(value: ChainLockup) -> Self {
+        SwapTransaction {
+            id: value.transaction.id,
+            hex: value.transaction.hex,
+            timeout_block_height: value.timeout.as_ref().map(|timeout| timeout.block_height),
+            timeout_eta: value.timeout.and_then(|timeout| timeout.eta),
+        }
+    }
+}
@@ -4,35 +4,35 @@ use boltz_client::elements;
 use pyo3::{PyErr, pyfunction};
 use pyo3::exceptions::PyValueError;
 
-use crate::utils::errors::handle_rust_error;
+use crate::utils::errors::{handle_rust_error_as, InvalidAddressError};
+use crate::utils::network::{parse_bitcoin_network, parse_liquid_address_params, INVALID_CHAIN};
 
 const COULD_NOT_PARSE_ADDRESS: &str = "could not parse address";
-const INVALID_NETWORK: &str = "invalid network";
-const INVALID_CHAIN: &str = "invalid chain";
 
 #[pyfunction]
 pub fn validate_address(chain: String, network: String, address: String) -> Result<bool, PyErr> {
     match chain.as_str() {
         "BTC" => {
-            let address = handle_rust_error(COULD_NOT_PARSE_ADDRESS, bitcoin::Address::from_str(address.as_str()))?;
-            let network = match network.as_str() {
-                "main" => bitcoin::Network::Bitcoin,
-                "testnet" => bitcoin::Network::Testnet,
-                "regtest" => bitcoin::Network::Regtest,
-                _ => return Err(PyValueError::new_err(INVALID_NETWORK)),
-            };
-            Ok(address.is_valid_for_network(network))
+            let address = handle_rust_error_as::<InvalidAddressError, _, _>(COULD_NOT_PARSE_ADDRESS, bitcoin::Address::from_str(address.as_str()))?;
+            Ok(address.is_valid_for_network(parse_bitcoin_network(network.as_str())?))
         },
         "L-BTC" => {
-            let address = handle_rust_error(COULD_NOT_PARSE_ADDRESS, elements::Address::from_str(address.as_str()))?;
-            let network = match network.as_str() {
-                "main" => elements::AddressParams::LIQUID,
-                "testnet" => elements::AddressParams::LIQUID_TESTNET,
-                "regtest" => elements::AddressParams::ELEMENTS,
-                _ => return Err(PyValueError::new_err(INVALID_NETWORK)),
-            };
+            let address = handle_rust_error_as::<InvalidAddressError, _, _>(COULD_NOT_PARSE_ADDRESS, elements::Address::from_str(address.as_str()))?;
+            Ok(address.params == parse_liquid_address_params(network.as_str())?)
+        },
+        _ => Err(PyValueError::new_err(INVALID_CHAIN)),
+    }
+}
 
-            Ok(address.params == &network)
+pub(crate) fn address_script_pubkey(chain: &str, address: &str) -> Result<Vec<u8>, PyErr> {
+    match chain {
+        "BTC" => {
+            let address = handle_rust_error_as::<InvalidAddressError, _, _>(COULD_NOT_PARSE_ADDRESS, bitcoin::Address::from_str(address))?;
+            Ok(address.assume_checked().script_pubkey().to_bytes())
+        },
+        "L-BTC" => {
+            let address = handle_rust_error_as::<InvalidAddressError, _, _>(COULD_NOT_PARSE_ADDRESS, elements::Address::from_str(address))?;
+            Ok(address.script_pubkey().to_bytes())
         },
         _ => Err(PyValueError::new_err(INVALID_CHAIN)),
     }
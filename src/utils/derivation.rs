@@ -0,0 +1,124 @@
+use std::str::FromStr;
+
+use bip39::Mnemonic;
+use bitcoin::bip32::{DerivationPath, Xpriv, Xpub};
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::key::rand::{thread_rng, RngCore};
+use bitcoin::secp256k1::{Keypair, Secp256k1};
+use bitcoin::Network;
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, PyResult};
+
+use crate::utils::errors::{handle_rust_error, handle_rust_error_as, InvalidKeyError};
+use crate::utils::preimage::Preimage;
+
+// Path of the swap keys in the rescue file of the Boltz web app, the index of
+// the swap is appended as last, unhardened, child
+pub(crate) const DERIVATION_PATH: &str = "m/44/0/0/0";
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct SwapKeyDeriver {
+    mnemonic: Option<String>,
+    // The mnemonic alone does not restore the keys when a passphrase was used
+    passphrase: bool,
+    root: Xpriv,
+}
+
+impl SwapKeyDeriver {
+    pub(crate) fn has_passphrase(&self) -> bool {
+        self.passphrase
+    }
+
+    pub(crate) fn keypair(&self, index: u32) -> PyResult<Keypair> {
+        let secp = Secp256k1::new();
+        let path = handle_rust_error(
+            "could not parse derivation path",
+            DerivationPath::from_str(&format!("{}/{}", DERIVATION_PATH, index)),
+        )?;
+        let key = handle_rust_error_as::<InvalidKeyError, _, _>("could not derive key", self.root.derive_priv(&secp, &path))?;
+
+        Ok(key.to_keypair(&secp))
+    }
+}
+
+#[pymethods]
+impl SwapKeyDeriver {
+    #[staticmethod]
+    #[pyo3(signature = (words=12))]
+    pub fn new_mnemonic(words: usize) -> PyResult<String> {
+        let entropy_len = match words {
+            12 => 16,
+            24 => 32,
+            _ => return Err(PyValueError::new_err("mnemonic has to have 12 or 24 words")),
+        };
+        let mut entropy = [0u8; 32];
+        thread_rng().fill_bytes(&mut entropy);
+
+        let mnemonic = handle_rust_error("could not generate mnemonic", Mnemonic::from_entropy(&entropy[..entropy_len]))?;
+        Ok(mnemonic.to_string())
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (mnemonic, passphrase=String::new()))]
+    pub fn from_mnemonic(mnemonic: String, passphrase: String) -> PyResult<Self> {
+        let parsed = handle_rust_error_as::<InvalidKeyError, _, _>("could not parse mnemonic", Mnemonic::parse(mnemonic.as_str()))?;
+        let root = handle_rust_error_as::<InvalidKeyError, _, _>(
+            "could not derive root key",
+            Xpriv::new_master(Network::Bitcoin, &parsed.to_seed(passphrase.as_str())),
+        )?;
+
+        Ok(SwapKeyDeriver {
+            mnemonic: Some(parsed.to_string()),
+            passphrase: !passphrase.is_empty(),
+            root,
+        })
+    }
+
+    #[staticmethod]
+    pub fn from_xprv(xprv: String) -> PyResult<Self> {
+        let root = handle_rust_error_as::<InvalidKeyError, _, _>("could not parse xprv", Xpriv::from_str(xprv.as_str()))?;
+
+        Ok(SwapKeyDeriver {
+            mnemonic: None,
+            passphrase: false,
+            root,
+        })
+    }
+
+    #[getter]
+    pub fn mnemonic(&self) -> Option<String> {
+        self.mnemonic.clone()
+    }
+
+    #[getter]
+    pub fn derivation_path(&self) -> String {
+        DERIVATION_PATH.to_string()
+    }
+
+    pub fn xprv(&self) -> String {
+        self.root.to_string()
+    }
+
+    // Extended public key of the swap key path, which is enough to look up
+    // swaps without exposing any private key
+    pub fn xpub(&self) -> PyResult<String> {
+        let secp = Secp256k1::new();
+        let path = handle_rust_error("could not parse derivation path", DerivationPath::from_str(DERIVATION_PATH))?;
+        let key = handle_rust_error_as::<InvalidKeyError, _, _>("could not derive key", self.root.derive_priv(&secp, &path))?;
+
+        Ok(Xpub::from_priv(&secp, &key).to_string())
+    }
+
+    pub fn derive_keys(&self, index: u32) -> PyResult<(Vec<u8>, Vec<u8>)> {
+        let keys = self.keypair(index)?;
+
+        Ok((keys.secret_key().as_ref().to_vec(), keys.public_key().serialize().to_vec()))
+    }
+
+    // The preimage is the SHA256 hash of the private key at the same index
+    pub fn derive_preimage(&self, index: u32) -> PyResult<Preimage> {
+        let keys = self.keypair(index)?;
+        Preimage::from_bytes(sha256::Hash::hash(keys.secret_key().as_ref()).to_byte_array().to_vec())
+    }
+}
@@ -1,13 +1,141 @@
 use pyo3::exceptions::PyValueError;
-use pyo3::{PyErr, PyTypeInfo};
+use pyo3::prelude::PyAnyMethods;
+use pyo3::types::PyModule;
+use pyo3::{create_exception, Bound, FromPyObject, PyErr, PyObject, PyResult, PyTypeInfo, Python, ToPyObject};
+
+// BoltzError extends ValueError, so code that caught the ValueError raised by
+// earlier versions keeps working
+create_exception!(boltz_client_bindings, BoltzError, PyValueError);
+create_exception!(boltz_client_bindings, ApiError, BoltzError);
+create_exception!(boltz_client_bindings, NetworkError, BoltzError);
+create_exception!(boltz_client_bindings, InvalidKeyError, BoltzError);
+create_exception!(boltz_client_bindings, InvalidAddressError, BoltzError);
+create_exception!(boltz_client_bindings, InvalidInvoiceError, BoltzError);
+create_exception!(boltz_client_bindings, ScriptError, BoltzError);
+create_exception!(boltz_client_bindings, SigningError, BoltzError);
+
+pub fn register_errors(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("BoltzError", py.get_type_bound::<BoltzError>())?;
+    m.add("ApiError", py.get_type_bound::<ApiError>())?;
+    m.add("NetworkError", py.get_type_bound::<NetworkError>())?;
+    m.add("InvalidKeyError", py.get_type_bound::<InvalidKeyError>())?;
+    m.add("InvalidAddressError", py.get_type_bound::<InvalidAddressError>())?;
+    m.add("InvalidInvoiceError", py.get_type_bound::<InvalidInvoiceError>())?;
+    m.add("ScriptError", py.get_type_bound::<ScriptError>())?;
+    m.add("SigningError", py.get_type_bound::<SigningError>())?;
+    Ok(())
+}
+
+fn new_error<E: PyTypeInfo>(msg: &str, detail: Option<String>, attrs: Vec<(&str, PyObject)>) -> PyErr {
+    let text = match &detail {
+        Some(detail) => format!("{}: {}", msg, detail),
+        None => msg.to_string(),
+    };
+
+    Python::with_gil(|py| {
+        let err = PyErr::new::<E, _>(text);
+        let value = err.value_bound(py);
+        let _ = value.setattr("message", msg);
+        let _ = value.setattr("detail", detail);
+        for (name, attr) in attrs {
+            let _ = value.setattr(name, attr);
+        }
+        err
+    })
+}
 
 pub fn to_python_error<E: PyTypeInfo, T: std::fmt::Debug>(msg: &str, err: T) -> PyErr {
-    PyErr::new::<E, _>(format!("{}: {:?}", msg, err))
+    new_error::<E>(msg, Some(format!("{:?}", err)), Vec::new())
+}
+
+pub fn new_python_error<E: PyTypeInfo>(msg: &str) -> PyErr {
+    new_error::<E>(msg, None, Vec::new())
+}
+
+pub fn api_error(msg: &str, status: Option<u16>, body: Option<String>) -> PyErr {
+    Python::with_gil(|py| {
+        let error = body.as_ref().and_then(|body| {
+            py.import_bound("json")
+                .and_then(|json| json.call_method1("loads", (body.as_str(),)))
+                .ok()
+                .map(|error| error.unbind())
+        });
+        new_error::<ApiError>(
+            msg,
+            body.clone(),
+            vec![
+                ("status", status.to_object(py)),
+                ("body", body.to_object(py)),
+                ("error", error.to_object(py)),
+            ],
+        )
+    })
+}
+
+fn api_error_attr<T: for<'py> FromPyObject<'py>>(err: &PyErr, name: &str) -> Option<T> {
+    Python::with_gil(|py| {
+        if !err.is_instance_of::<ApiError>(py) {
+            return None;
+        }
+        err.value_bound(py).getattr(name).ok()?.extract().ok()
+    })
+}
+
+// Status code of an ApiError raised for an HTTP response, None for every
+// other error
+pub fn api_error_status(err: &PyErr) -> Option<u16> {
+    api_error_attr(err, "status")
+}
+
+pub fn api_error_body(err: &PyErr) -> Option<String> {
+    api_error_attr(err, "body")
 }
 
 pub fn handle_rust_error<T, E: std::fmt::Debug>(msg: &str, res: Result<T, E>) -> Result<T, PyErr> {
+    handle_rust_error_as::<BoltzError, T, E>(msg, res)
+}
+
+pub fn handle_rust_error_as<X: PyTypeInfo, T, E: std::fmt::Debug>(msg: &str, res: Result<T, E>) -> Result<T, PyErr> {
+    match res {
+        Ok(r) => Ok(r),
+        Err(err) => Err(to_python_error::<X, _>(msg, err)),
+    }
+}
+
+pub fn handle_http_error<T>(msg: &str, res: Result<T, ureq::Error>) -> Result<T, PyErr> {
+    match res {
+        Ok(r) => Ok(r),
+        Err(ureq::Error::Status(status, response)) => Err(api_error(msg, Some(status), response.into_string().ok())),
+        Err(err) => Err(to_python_error::<NetworkError, _>(msg, err)),
+    }
+}
+
+// boltz_client flattens HTTP errors into a string, the status code is
+// recovered from the ureq error message when it is there
+fn parse_status(err: &str) -> Option<u16> {
+    let (_, rest) = err.split_once("status code ")?;
+    rest.chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+pub fn handle_boltz_error<X: PyTypeInfo, T>(msg: &str, res: Result<T, boltz_client::error::Error>) -> Result<T, PyErr> {
+    use boltz_client::error::Error;
+
     match res {
         Ok(r) => Ok(r),
-        Err(err) => Err(to_python_error::<PyValueError, _>(msg, err)),
+        Err(Error::HTTP(err)) => match parse_status(err.as_str()) {
+            Some(status) => Err(api_error(msg, Some(status), None)),
+            None => Err(to_python_error::<NetworkError, _>(msg, err)),
+        },
+        Err(err @ Error::Electrum(_)) => Err(to_python_error::<NetworkError, _>(msg, err)),
+        Err(err @ Error::Key(_)) => Err(to_python_error::<InvalidKeyError, _>(msg, err)),
+        Err(err @ Error::Address(_)) => Err(to_python_error::<InvalidAddressError, _>(msg, err)),
+        Err(err @ Error::Bolt11(_)) => Err(to_python_error::<InvalidInvoiceError, _>(msg, err)),
+        Err(err @ Error::Taproot(_)) => Err(to_python_error::<ScriptError, _>(msg, err)),
+        Err(err) => Err(to_python_error::<X, _>(msg, err)),
     }
 }
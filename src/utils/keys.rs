@@ -1,15 +1,14 @@
-use bitcoin::{key::rand::thread_rng, secp256k1::Keypair};
+use bitcoin::{key::rand::thread_rng, secp256k1::Keypair, secp256k1::Secp256k1};
 use boltz_client::{PublicKey, ToHex};
-use pyo3::exceptions::PyValueError;
 use pyo3::{pyfunction, PyErr};
 
-use crate::utils::errors::to_python_error;
+use crate::utils::errors::{to_python_error, InvalidKeyError};
 
 pub fn parse_public_key(public_key: Vec<u8>) -> Result<PublicKey, PyErr> {
     match PublicKey::from_slice(public_key.as_slice()) {
         Ok(k) => Ok(k),
         Err(err) => {
-            return Err(to_python_error::<PyValueError, _>(
+            return Err(to_python_error::<InvalidKeyError, _>(
                 "could not parse public key",
                 err,
             ));
@@ -17,6 +16,16 @@ pub fn parse_public_key(public_key: Vec<u8>) -> Result<PublicKey, PyErr> {
     }
 }
 
+pub fn parse_keypair(secret_key: Vec<u8>) -> Result<Keypair, PyErr> {
+    match Keypair::from_seckey_slice(&Secp256k1::new(), secret_key.as_slice()) {
+        Ok(k) => Ok(k),
+        Err(err) => Err(to_python_error::<InvalidKeyError, _>(
+            "could not parse secret key",
+            err,
+        )),
+    }
+}
+
 #[pyfunction]
 pub fn new_keys() -> (Vec<u8>, Vec<u8>) {
     let secp = bitcoin::secp256k1::Secp256k1::new();
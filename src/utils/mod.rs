@@ -1,3 +1,6 @@
 pub mod errors;
 pub mod keys;
 pub mod address;
+pub mod derivation;
+pub mod network;
+pub mod preimage;
@@ -0,0 +1,38 @@
+use boltz_client::elements::AddressParams;
+use boltz_client::network::Chain;
+use pyo3::exceptions::PyValueError;
+use pyo3::PyErr;
+
+pub const INVALID_NETWORK: &str = "invalid network";
+pub const INVALID_CHAIN: &str = "invalid chain";
+
+pub fn parse_chain(chain: &str, network: &str) -> Result<Chain, PyErr> {
+    match (chain, network) {
+        ("BTC", "main") => Ok(Chain::Bitcoin),
+        ("BTC", "testnet") => Ok(Chain::BitcoinTestnet),
+        ("BTC", "regtest") => Ok(Chain::BitcoinRegtest),
+        ("L-BTC", "main") => Ok(Chain::Liquid),
+        ("L-BTC", "testnet") => Ok(Chain::LiquidTestnet),
+        ("L-BTC", "regtest") => Ok(Chain::LiquidRegtest),
+        ("BTC", _) | ("L-BTC", _) => Err(PyValueError::new_err(INVALID_NETWORK)),
+        _ => Err(PyValueError::new_err(INVALID_CHAIN)),
+    }
+}
+
+pub fn parse_bitcoin_network(network: &str) -> Result<bitcoin::Network, PyErr> {
+    match network {
+        "main" => Ok(bitcoin::Network::Bitcoin),
+        "testnet" => Ok(bitcoin::Network::Testnet),
+        "regtest" => Ok(bitcoin::Network::Regtest),
+        _ => Err(PyValueError::new_err(INVALID_NETWORK)),
+    }
+}
+
+pub fn parse_liquid_address_params(network: &str) -> Result<&'static AddressParams, PyErr> {
+    match network {
+        "main" => Ok(&AddressParams::LIQUID),
+        "testnet" => Ok(&AddressParams::LIQUID_TESTNET),
+        "regtest" => Ok(&AddressParams::ELEMENTS),
+        _ => Err(PyValueError::new_err(INVALID_NETWORK)),
+    }
+}
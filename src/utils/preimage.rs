@@ -0,0 +1,85 @@
+use std::str::FromStr;
+
+use bitcoin::hashes::{hash160, sha256, Hash};
+use bitcoin::key::rand::{thread_rng, RngCore};
+use boltz_client::Bolt11Invoice;
+use pyo3::exceptions::PyValueError;
+use pyo3::{pyclass, pymethods, FromPyObject, PyRef, PyResult};
+
+use crate::utils::errors::{handle_rust_error, handle_rust_error_as, InvalidInvoiceError};
+
+#[pyclass]
+#[derive(Debug, Clone)]
+pub struct Preimage {
+    bytes: [u8; 32],
+}
+
+#[pymethods]
+impl Preimage {
+    #[staticmethod]
+    pub fn new() -> Self {
+        let mut bytes = [0u8; 32];
+        thread_rng().fill_bytes(&mut bytes);
+
+        Preimage { bytes }
+    }
+
+    #[staticmethod]
+    pub fn from_bytes(data: Vec<u8>) -> PyResult<Self> {
+        let bytes: [u8; 32] = data
+            .try_into()
+            .map_err(|_| PyValueError::new_err("preimage has to be 32 bytes"))?;
+
+        Ok(Preimage { bytes })
+    }
+
+    #[staticmethod]
+    pub fn from_hex(preimage: String) -> PyResult<Self> {
+        let bytes = handle_rust_error("could not decode preimage", hex::decode(preimage))?;
+        Preimage::from_bytes(bytes)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.bytes.to_vec()
+    }
+
+    pub fn hex(&self) -> String {
+        hex::encode(self.bytes)
+    }
+
+    pub fn sha256(&self) -> Vec<u8> {
+        sha256::Hash::hash(&self.bytes).to_byte_array().to_vec()
+    }
+
+    pub fn hash160(&self) -> Vec<u8> {
+        hash160::Hash::hash(&self.bytes).to_byte_array().to_vec()
+    }
+
+    pub fn matches_invoice(&self, invoice: String) -> PyResult<bool> {
+        let invoice = handle_rust_error_as::<InvalidInvoiceError, _, _>("could not parse invoice", Bolt11Invoice::from_str(invoice.as_str()))?;
+
+        Ok(sha256::Hash::hash(&self.bytes) == *invoice.payment_hash())
+    }
+}
+
+impl Default for Preimage {
+    fn default() -> Self {
+        Preimage::new()
+    }
+}
+
+// Claim transactions take the preimage as hex or as Preimage
+#[derive(FromPyObject)]
+pub(crate) enum PreimageArg<'py> {
+    Hex(String),
+    Preimage(PyRef<'py, Preimage>),
+}
+
+impl PreimageArg<'_> {
+    pub fn into_hex(self) -> String {
+        match self {
+            PreimageArg::Hex(hex) => hex,
+            PreimageArg::Preimage(preimage) => preimage.hex(),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+import base64
+import json
+import threading
+from http.server import BaseHTTPRequestHandler, HTTPServer
+
+import pytest
+
+from boltz_client_bindings import ApiError, BitcoindRpcBackend
+
+
+SCRIPT = bytes.fromhex("0014751e76e8199196d454941c45d1b3a323f1433bd6")
+
+
+class RpcStub(BaseHTTPRequestHandler):
+    """Answers JSON-RPC calls like bitcoind, authenticated with the current cookie."""
+
+    cookie = "__cookie__:first"
+    tip = 5
+    unspent = []
+
+    def do_POST(self):
+        expected = "Basic " + base64.b64encode(self.cookie.encode()).decode()
+        if self.headers.get("Authorization") != expected:
+            self.send_error(401)
+            return
+
+        request = json.loads(self.rfile.read(int(self.headers["Content-Length"])))
+        results = {"getblockcount": self.tip, "listunspent": self.unspent}
+        if request["method"] in results:
+            status, body = 200, {"result": results[request["method"]], "error": None, "id": request["id"]}
+        else:
+            # bitcoind reports RPC errors with HTTP 500
+            error = {"code": -5, "message": "No such mempool or blockchain transaction"}
+            status, body = 500, {"result": None, "error": error, "id": request["id"]}
+        self.send_response(status)
+        self.end_headers()
+        self.wfile.write(json.dumps(body).encode())
+
+    def log_message(self, *args):
+        pass
+
+
+@pytest.fixture
+def bitcoind(tmp_path):
+    RpcStub.cookie = "__cookie__:first"
+    RpcStub.tip = 5
+    RpcStub.unspent = []
+    cookie_file = tmp_path / ".cookie"
+    cookie_file.write_text(RpcStub.cookie)
+    httpd = HTTPServer(("127.0.0.1", 0), RpcStub)
+    threading.Thread(target=httpd.serve_forever, daemon=True).start()
+    yield RpcStub, cookie_file, f"http://127.0.0.1:{httpd.server_address[1]}"
+    httpd.shutdown()
+
+
+def test_cookie_is_read_again_after_restart(bitcoind):
+    stub, cookie_file, url = bitcoind
+    backend = BitcoindRpcBackend("regtest", url, cookie_file=str(cookie_file))
+    assert backend.get_tip_height() == 5
+
+    # bitcoind restarted and wrote a new cookie
+    stub.cookie = "__cookie__:second"
+    cookie_file.write_text(stub.cookie)
+    assert backend.get_tip_height() == 5
+
+    # a cookie that is still rejected after reading it again is an error
+    stub.cookie = "__cookie__:third"
+    with pytest.raises(ApiError) as err:
+        backend.get_tip_height()
+    assert err.value.status == 401
+
+
+def test_utxo_height_does_not_underflow(bitcoind):
+    stub, cookie_file, url = bitcoind
+    stub.unspent = [
+        {"txid": "00" * 32, "vout": 0, "scriptPubKey": SCRIPT.hex(), "amount": 0.0005, "confirmations": 10},
+        {"txid": "11" * 32, "vout": 1, "scriptPubKey": SCRIPT.hex(), "amount": 0.0005, "confirmations": 2},
+    ]
+    backend = BitcoindRpcBackend("regtest", url, wallet="boltz", cookie_file=str(cookie_file))
+
+    utxos = backend.get_utxos(SCRIPT)
+    assert [utxo.height for utxo in utxos] == [0, 4]
+    assert utxos[0].value == 50_000
+
+
+def test_rpc_error(bitcoind):
+    _, cookie_file, url = bitcoind
+    backend = BitcoindRpcBackend("regtest", url, cookie_file=str(cookie_file))
+
+    with pytest.raises(ApiError) as err:
+        backend.get_transaction("00" * 32)
+    assert "No such mempool or blockchain transaction (-5)" in str(err.value)
@@ -0,0 +1,130 @@
+import json
+import os
+import socketserver
+import threading
+
+import pytest
+
+import boltz_client_bindings
+from boltz_client_bindings import Client, ElectrumClient, ElectrumConfig, MockBoltzServer
+
+
+SCRIPT = bytes.fromhex("0014751e76e8199196d454941c45d1b3a323f1433bd6")
+
+
+class ElectrumStub(socketserver.StreamRequestHandler):
+    """Answers Electrum JSON-RPC requests from class attributes, one JSON object per line."""
+
+    height = 1000
+    fee = 0.00012
+    history = []
+    unspent = []
+    transactions = {}
+    broadcast = []
+
+    def handle(self):
+        for line in self.rfile:
+            request = json.loads(line)
+            try:
+                response = {"result": self.result(request["method"], request["params"])}
+            except KeyError as err:
+                response = {"error": {"code": 1, "message": f"unknown {err}"}}
+            response.update({"jsonrpc": "2.0", "id": request["id"]})
+            self.wfile.write(json.dumps(response).encode() + b"\n")
+
+    def result(self, method, params):
+        if method == "server.version":
+            return ["ElectrumStub", "1.4"]
+        if method == "blockchain.headers.subscribe":
+            return {"height": self.height, "hex": "00" * 80}
+        if method == "blockchain.estimatefee":
+            return self.fee
+        if method == "blockchain.scripthash.get_history":
+            return self.history
+        if method == "blockchain.scripthash.listunspent":
+            return self.unspent
+        if method == "blockchain.transaction.get":
+            return self.transactions[params[0]]
+        if method == "blockchain.transaction.broadcast":
+            self.broadcast.append(params[0])
+            return "ab" * 32
+        raise KeyError(method)
+
+
+@pytest.fixture
+def electrum():
+    ElectrumStub.fee = 0.00012
+    ElectrumStub.history = []
+    ElectrumStub.unspent = []
+    ElectrumStub.transactions = {}
+    ElectrumStub.broadcast = []
+    server = socketserver.ThreadingTCPServer(("127.0.0.1", 0), ElectrumStub)
+    server.daemon_threads = True
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    yield ElectrumStub, f"tcp://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+    server.server_close()
+
+
+def test_bitcoin(electrum):
+    stub, url = electrum
+    stub.unspent = [{"tx_hash": "11" * 32, "tx_pos": 1, "height": 990, "value": 50_000}]
+    client = ElectrumClient(ElectrumConfig("BTC", "regtest", url, tls=False))
+
+    assert client.get_tip_height() == 1000
+    # 0.00012 BTC/kvB are 12 sat/vB
+    assert client.estimate_fee() == pytest.approx(12.0)
+
+    utxos = client.get_utxos(SCRIPT)
+    assert [(utxo.txid, utxo.vout, utxo.value, utxo.height) for utxo in utxos] == [("11" * 32, 1, 50_000, 990)]
+
+    assert client.broadcast("0200") == "ab" * 32
+    assert stub.broadcast == ["0200"]
+
+
+def test_no_fee_estimate(electrum):
+    stub, url = electrum
+    stub.fee = -1
+    client = ElectrumClient(ElectrumConfig("BTC", "regtest", url, tls=False))
+    with pytest.raises(boltz_client_bindings.NetworkError):
+        client.estimate_fee()
+
+
+def test_liquid_utxos_skip_spent_outputs(electrum):
+    stub, url = electrum
+    with MockBoltzServer() as server:
+        boltz = Client(server.url)
+        secret_key, public_key = boltz_client_bindings.new_keys()
+        preimage = boltz_client_bindings.Preimage.new()
+        response = boltz.create_reverse_swap("BTC", "L-BTC", preimage.sha256(), public_key, 100_000)
+        script = boltz_client_bindings.LBtcSwapScript.from_reverse_response(response, public_key)
+        other = boltz.create_reverse_swap("BTC", "L-BTC", os.urandom(32), public_key, 100_000)
+
+        # two lockups of the same swap, the first of which is claimed
+        spent_tx = server.fund_swap(response.id)
+        spent_txid = boltz.get_swap_status(response.id).transaction_id
+        unspent_tx = server.fund_swap(response.id)
+        unspent_txid = boltz.get_swap_status(response.id).transaction_id
+
+        claim = boltz_client_bindings.LBtcSwapTx.new_claim(script, other.lockup_address, spent_tx, "regtest")
+        claim_tx = claim.sign_claim(secret_key, preimage, 100, cooperative=False)
+        claim_txid = boltz.broadcast_transaction("L-BTC", claim_tx)
+
+    # the mock lockups have a single input without script sig and a single
+    # blinded output, whose script follows the three 33 byte commitments
+    lockup_script = bytes.fromhex(unspent_tx)[148:182]
+    assert lockup_script[:2] == b"\x51\x20"
+
+    stub.history = [
+        {"tx_hash": spent_txid, "height": 990},
+        {"tx_hash": unspent_txid, "height": 0},
+        {"tx_hash": claim_txid, "height": 0},
+    ]
+    stub.transactions = {spent_txid: spent_tx, unspent_txid: unspent_tx, claim_txid: claim_tx}
+    client = ElectrumClient(ElectrumConfig("L-BTC", "regtest", url, tls=False))
+
+    utxos = client.get_utxos(lockup_script)
+    assert [(utxo.txid, utxo.vout, utxo.height) for utxo in utxos] == [(unspent_txid, 0, None)]
+    # confidential values are unknown to the server
+    assert utxos[0].value is None
+    assert [item.txid for item in client.get_script_history(lockup_script)] == [spent_txid, unspent_txid, claim_txid]
@@ -0,0 +1,66 @@
+import hashlib
+import json
+
+import pytest
+
+from boltz_client_bindings import RescueFile, SwapKeyDeriver
+
+
+MNEMONIC = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+ROOT_XPRV = "xprv9s21ZrQH143K3GJpoapnV8SFfukcVBSfeCficPSGfubmSFDxo1kuHnLisriDvSnRRuL2Qrg5ggqHKNVpxR86QEC8w35uxmGoggxtQTPvfUu"
+
+
+def test_derivation_is_deterministic():
+    deriver = SwapKeyDeriver.from_mnemonic(MNEMONIC)
+    assert deriver.xprv() == ROOT_XPRV
+
+    restored = SwapKeyDeriver.from_xprv(ROOT_XPRV)
+    assert restored.derive_keys(7) == deriver.derive_keys(7)
+    assert restored.derive_keys(7) != deriver.derive_keys(8)
+    assert restored.xpub() == deriver.xpub()
+
+
+def test_derived_keys_match_web_app():
+    # keys of m/44/0/0/0/0 and m/44/0/0/0/1, as derived by the Boltz web app
+    deriver = SwapKeyDeriver.from_mnemonic(MNEMONIC)
+    assert deriver.derive_keys(0) == (
+        bytes.fromhex("a6609953f7219279e8164e041869ba9d90dd768f97394d78d390c5538c8bc9d0"),
+        bytes.fromhex("03ce83ae7b2ef20f50d7adab682b25111df74ee40121e021cd8b6cad8a93f78fec"),
+    )
+    assert deriver.derive_keys(1) == (
+        bytes.fromhex("9482530f20d75f34bcfdc51234584057a1feff287e4950240e268a8c1516156d"),
+        bytes.fromhex("03375922422d2d9899ceeb625d92d4bfe8ee2354777a28e2d5ea83da206926e561"),
+    )
+
+
+def test_derived_preimage():
+    deriver = SwapKeyDeriver.from_mnemonic(MNEMONIC)
+    secret_key, _ = deriver.derive_keys(0)
+
+    # the preimage is the SHA256 hash of the private key at the same index
+    preimage = deriver.derive_preimage(0)
+    assert preimage.hex() == "03c0b3323daab895d806870bd1f050bdca624a24882d3e317b151d537fa75bb7"
+    assert preimage.to_bytes() == hashlib.sha256(secret_key).digest()
+
+
+def test_new_mnemonic():
+    mnemonic = SwapKeyDeriver.new_mnemonic()
+    assert len(mnemonic.split()) == 12
+    assert SwapKeyDeriver.from_mnemonic(mnemonic).mnemonic == mnemonic
+    assert len(SwapKeyDeriver.new_mnemonic(24).split()) == 24
+
+
+def test_rescue_file_keys():
+    data = json.loads(RescueFile([], SwapKeyDeriver.from_mnemonic(MNEMONIC)).to_json())
+    assert data["mnemonic"] == MNEMONIC
+
+    deriver = SwapKeyDeriver.from_mnemonic(MNEMONIC, "passphrase")
+    data = json.loads(RescueFile([], deriver).to_json())
+    assert "mnemonic" not in data
+    restored = RescueFile.from_json(json.dumps(data)).deriver
+    assert restored.derive_keys(0) == deriver.derive_keys(0)
+
+
+def test_mnemonic_only_rescue_file_needs_client():
+    with pytest.raises(ValueError):
+        RescueFile.from_json(json.dumps({"mnemonic": MNEMONIC}))
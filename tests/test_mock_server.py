@@ -0,0 +1,598 @@
+import asyncio
+import hashlib
+import json
+import os
+import threading
+import time
+from http.server import BaseHTTPRequestHandler, HTTPServer
+
+import pytest
+
+import boltz_client_bindings
+from boltz_client_bindings import Client, MockBoltzServer, SwapStatus
+
+
+REFUND_ADDRESS = "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080"
+
+
+@pytest.fixture
+def server():
+    with MockBoltzServer() as server:
+        yield server
+
+
+def test_pairs_and_height(server):
+    client = Client(server.url)
+
+    assert client.get_submarine_pairs().btc["BTC"].fees.percentage == 0.1
+    assert "L-BTC" in client.get_reverse_pairs().btc
+    assert client.get_height().btc == 1000
+
+    server.set_height("BTC", 1234)
+    assert client.get_height().btc == 1234
+
+
+def test_submarine_swap(server):
+    client = Client(server.url)
+    secret_key, public_key = boltz_client_bindings.new_keys()
+    invoice = server.create_invoice(50_000, hashlib.sha256(os.urandom(32)).digest())
+
+    response = client.create_submarine_swap("BTC", "BTC", invoice, public_key, None)
+    script = boltz_client_bindings.BtcSwapScript.from_submarine_response(response, public_key)
+    pair = client.get_submarine_pairs().btc["BTC"]
+    height = client.get_height().btc
+    script.verify(response, invoice, "regtest", height, pair)
+    script.verify(response, invoice, "regtest", height, max_fee=response.expected_amount - 50_000)
+
+    with pytest.raises(ValueError):
+        script.verify(response, invoice, "regtest", height)
+    with pytest.raises(boltz_client_bindings.BoltzError):
+        script.verify(response, invoice, "regtest", height, pair, max_fee=0)
+    with pytest.raises(ValueError):
+        script.verify(response, invoice, "regtest", height, pair, max_timeout_delta=2**64 - 1)
+
+    assert client.get_swap_status(response.id).status == SwapStatus.InvoiceSet
+
+    lockup_tx = server.fund_swap(response.id)
+    status = client.get_swap_status(response.id)
+    assert status.status == SwapStatus.TransactionMempool
+    assert status.transaction_hex == lockup_tx
+
+    submarine_tx = client.get_submarine_transaction(response.id)
+    assert submarine_tx.hex == lockup_tx
+    assert submarine_tx.timeout_block_height == response.timeout_block_height
+
+    tx = boltz_client_bindings.BtcSwapTx.new_refund(script, REFUND_ADDRESS, submarine_tx, "regtest")
+    refund_tx = tx.sign_refund(secret_key, 500, cooperative=False)
+
+    refund_txid = client.broadcast_transaction("BTC", refund_tx)
+    assert client.get_transaction("BTC", refund_txid) == refund_tx
+
+    with pytest.raises(boltz_client_bindings.InvalidAddressError):
+        boltz_client_bindings.BtcSwapTx.new_refund(script, REFUND_ADDRESS, submarine_tx, "main")
+
+
+def test_submarine_cooperative_claim(server):
+    client = Client(server.url)
+    secret_key, public_key = boltz_client_bindings.new_keys()
+    preimage = os.urandom(32)
+    invoice = server.create_invoice(50_000, hashlib.sha256(preimage).digest())
+
+    response = client.create_submarine_swap("BTC", "BTC", invoice, public_key, None)
+    script = boltz_client_bindings.BtcSwapScript.from_submarine_response(response, public_key)
+    server.fund_swap(response.id)
+
+    with pytest.raises(boltz_client_bindings.InvalidInvoiceError):
+        server.pay_invoice(response.id, os.urandom(32))
+    server.pay_invoice(response.id, preimage)
+    assert client.get_swap_status(response.id).status == SwapStatus.TransactionClaimPending
+
+    script.sign_submarine_claim(client, secret_key, invoice)
+    assert client.get_swap_status(response.id).status == SwapStatus.TransactionClaimed
+
+
+def test_cooperative_refund(server):
+    client = Client(server.url)
+    secret_key, public_key = boltz_client_bindings.new_keys()
+    invoice = server.create_invoice(50_000, hashlib.sha256(os.urandom(32)).digest())
+
+    response = client.create_submarine_swap("BTC", "BTC", invoice, public_key, None)
+    script = boltz_client_bindings.BtcSwapScript.from_submarine_response(response, public_key)
+    lockup_tx = server.fund_swap(response.id)
+    tx = boltz_client_bindings.BtcSwapTx.new_refund(script, REFUND_ADDRESS, lockup_tx, "regtest", client)
+
+    # Boltz only cooperates once the swap failed
+    with pytest.raises(boltz_client_bindings.ApiError) as err:
+        tx.sign_refund(secret_key, 500)
+    assert err.value.status == 400
+
+    server.push_status(response.id, SwapStatus.InvoiceFailedToPay)
+    refund_tx = tx.sign_refund(secret_key, 500)
+    assert refund_tx != tx.sign_refund(secret_key, 500, cooperative=False)
+
+    refund_txid = client.broadcast_transaction("BTC", refund_tx)
+    assert client.get_transaction("BTC", refund_txid) == refund_tx
+
+
+def test_cooperative_refund_refused(server):
+    client = Client(server.url)
+    secret_key, public_key = boltz_client_bindings.new_keys()
+    invoice = server.create_invoice(50_000, hashlib.sha256(os.urandom(32)).digest())
+
+    response = client.create_submarine_swap("BTC", "BTC", invoice, public_key, None)
+    script = boltz_client_bindings.BtcSwapScript.from_submarine_response(response, public_key)
+    lockup_tx = server.fund_swap(response.id)
+    server.set_response("POST", f"/swap/submarine/{response.id}/refund", 400, '{"error": "swap not eligible"}')
+
+    tx = boltz_client_bindings.BtcSwapTx.new_refund(script, REFUND_ADDRESS, lockup_tx, "regtest", client)
+    with pytest.raises(boltz_client_bindings.ApiError) as err:
+        tx.sign_refund(secret_key, 500)
+    assert err.value.status == 400
+
+
+def test_reverse_swap(server):
+    client = Client(server.url)
+    _, public_key = boltz_client_bindings.new_keys()
+    preimage_hash = hashlib.sha256(os.urandom(32)).digest()
+
+    response = client.create_reverse_swap("BTC", "L-BTC", preimage_hash, public_key, 100_000)
+    script = boltz_client_bindings.LBtcSwapScript.from_reverse_response(response, public_key)
+    assert script.lockup_address("regtest") == response.lockup_address
+
+
+def test_reverse_swap_claim(server):
+    client = Client(server.url)
+    secret_key, public_key = boltz_client_bindings.new_keys()
+    preimage = boltz_client_bindings.Preimage.new()
+
+    response = client.create_reverse_swap("BTC", "BTC", preimage.sha256(), public_key, 100_000)
+    assert preimage.matches_invoice(response.invoice)
+    script = boltz_client_bindings.BtcSwapScript.from_reverse_response(response, public_key)
+    assert script.is_reverse()
+
+    lockup_tx = server.fund_swap(response.id)
+    swap_tx = client.get_swap_transaction(response.id)
+    assert swap_tx.hex == lockup_tx
+
+    tx = boltz_client_bindings.BtcSwapTx.new_claim(script, REFUND_ADDRESS, swap_tx, "regtest")
+    assert tx.sign_claim(secret_key, preimage, 500, cooperative=False).startswith("02000000")
+
+    tx = boltz_client_bindings.BtcSwapTx.new_claim(script, REFUND_ADDRESS, swap_tx, "regtest", client)
+    claim_tx = tx.sign_claim(secret_key, preimage, 500)
+    assert client.get_transaction("BTC", client.broadcast_transaction("BTC", claim_tx)) == claim_tx
+
+    # the hashlock leaf is used when Boltz does not cooperate
+    server.set_response("POST", f"/swap/reverse/{response.id}/claim", 400, '{"error": "invalid preimage"}')
+    tx = boltz_client_bindings.BtcSwapTx.new_claim(script, REFUND_ADDRESS, swap_tx, "regtest", client)
+    script_path_tx = tx.sign_claim(secret_key, preimage, 500, cooperative=False)
+    fallback_tx = tx.sign_claim(secret_key, preimage, 500)
+    assert fallback_tx != claim_tx
+    assert len(fallback_tx) == len(script_path_tx)
+    with pytest.raises(boltz_client_bindings.ApiError):
+        tx.sign_claim(secret_key, preimage, 500, fallback=False)
+
+
+def test_chain_swap(server):
+    client = Client(server.url)
+    pair = client.get_chain_pairs().btc["L-BTC"]
+    assert pair.fees.percentage == 0.1
+    assert pair.fees.miner_fees.server == 30
+    assert "BTC" not in client.get_chain_pairs().btc
+
+    _, claim_key = boltz_client_bindings.new_keys()
+    _, refund_key = boltz_client_bindings.new_keys()
+    preimage = boltz_client_bindings.Preimage.new()
+    response = client.create_chain_swap(
+        "BTC", "L-BTC", preimage.sha256(), claim_key, refund_key, user_lock_amount=100_000, pair_hash=pair.hash
+    )
+
+    lockup = response.lockup_details
+    assert lockup.amount == 100_000
+    assert lockup.timeout_block_height == 1000 + 2 * 144
+    assert lockup.blinding_key is None
+    assert lockup.bip21.startswith(f"bitcoin:{lockup.lockup_address}?amount=0.001")
+
+    claim = response.claim_details
+    assert claim.amount == 100_000 - 100 - 30
+    assert claim.timeout_block_height == 1000 + 1440
+    assert claim.blinding_key is not None
+    assert claim.server_public_key == lockup.server_public_key
+
+    script = boltz_client_bindings.BtcSwapScript.from_chain_response(response, "lockup", refund_key)
+    assert script.is_chain()
+    assert script.lockup_address("regtest") == lockup.lockup_address
+    script = boltz_client_bindings.LBtcSwapScript.from_chain_response(response, "claim", claim_key)
+    assert script.lockup_address("regtest") == claim.lockup_address
+
+    with pytest.raises(boltz_client_bindings.ApiError):
+        client.create_chain_swap("BTC", "BTC", preimage.sha256(), claim_key, refund_key, user_lock_amount=100_000)
+
+
+def liquid_address(client):
+    # the confidential lockup address of another swap is a valid output address
+    _, public_key = boltz_client_bindings.new_keys()
+    response = client.create_reverse_swap("BTC", "L-BTC", os.urandom(32), public_key, 100_000)
+    script = boltz_client_bindings.LBtcSwapScript.from_reverse_response(response, public_key)
+    return script.lockup_address("regtest")
+
+
+def test_liquid_reverse_swap_claim(server):
+    client = Client(server.url)
+    secret_key, public_key = boltz_client_bindings.new_keys()
+    preimage = boltz_client_bindings.Preimage.new()
+
+    response = client.create_reverse_swap("BTC", "L-BTC", preimage.sha256(), public_key, 100_000)
+    script = boltz_client_bindings.LBtcSwapScript.from_reverse_response(response, public_key)
+    assert script.lockup_address("regtest") == response.lockup_address
+
+    lockup_tx = server.fund_swap(response.id)
+    swap_tx = client.get_swap_transaction(response.id)
+    assert swap_tx.hex == lockup_tx
+
+    tx = boltz_client_bindings.LBtcSwapTx.new_claim(script, liquid_address(client), swap_tx, "regtest")
+    assert tx.unblinded_amount() == response.onchain_amount
+
+    claim_tx = tx.sign_claim(secret_key, preimage, 100, cooperative=False)
+    assert client.get_transaction("L-BTC", client.broadcast_transaction("L-BTC", claim_tx)) == claim_tx
+
+    with pytest.raises(ValueError):
+        boltz_client_bindings.LBtcSwapTx.new_refund(script, liquid_address(client), swap_tx, "regtest")
+
+
+def test_liquid_submarine_swap_refund(server):
+    client = Client(server.url)
+    secret_key, public_key = boltz_client_bindings.new_keys()
+    invoice = server.create_invoice(50_000, hashlib.sha256(os.urandom(32)).digest())
+
+    response = client.create_submarine_swap("L-BTC", "BTC", invoice, public_key, None)
+    script = boltz_client_bindings.LBtcSwapScript.from_submarine_response(response, public_key)
+    assert script.lockup_address("regtest") == response.address
+
+    lockup_tx = server.fund_swap(response.id)
+    submarine_tx = client.get_submarine_transaction(response.id)
+    assert submarine_tx.hex == lockup_tx
+
+    tx = boltz_client_bindings.LBtcSwapTx.new_refund(script, liquid_address(client), submarine_tx, "regtest")
+    assert tx.unblinded_amount() == response.expected_amount
+
+    refund_tx = tx.sign_refund(secret_key, 100, cooperative=False)
+    assert client.get_transaction("L-BTC", client.broadcast_transaction("L-BTC", refund_tx)) == refund_tx
+
+    with pytest.raises(boltz_client_bindings.InvalidAddressError):
+        boltz_client_bindings.LBtcSwapTx.new_refund(script, REFUND_ADDRESS, submarine_tx, "regtest")
+
+
+def test_swap_transaction_fallback(server):
+    client = Client(server.url, config=boltz_client_bindings.ClientConfig(retries=0))
+    _, public_key = boltz_client_bindings.new_keys()
+    preimage = boltz_client_bindings.Preimage.new()
+    response = client.create_reverse_swap("BTC", "BTC", preimage.sha256(), public_key, 100_000)
+    lockup_tx = server.fund_swap(response.id)
+    path = f"/swap/reverse/{response.id}/transaction"
+
+    # server errors are no reason to look for a chain swap
+    server.set_response("GET", path, 503, '{"error": "maintenance"}')
+    with pytest.raises(boltz_client_bindings.ApiError) as err:
+        client.get_swap_transaction(response.id)
+    assert err.value.status == 503
+
+    # the mock serves the reverse swap from the chain endpoint as well
+    for status, body in ((404, '{"error": "not found"}'), (400, '{"error": "swap is not a reverse swap"}')):
+        server.clear_responses()
+        server.set_response("GET", path, status, body)
+        assert client.get_swap_transaction(response.id).hex == lockup_tx
+
+
+def test_rescue_file(server):
+    client = Client(server.url)
+    deriver = boltz_client_bindings.SwapKeyDeriver.from_mnemonic(boltz_client_bindings.SwapKeyDeriver.new_mnemonic())
+    _, public_key = deriver.derive_keys(3)
+    invoice = server.create_invoice(50_000, hashlib.sha256(os.urandom(32)).digest())
+
+    response = client.create_submarine_swap("BTC", "BTC", invoice, public_key, None)
+    swap = boltz_client_bindings.RescueSwap.from_submarine(response, "BTC", "BTC", key_index=3)
+    data = boltz_client_bindings.RescueFile([swap], deriver).to_json()
+
+    rescue_file = boltz_client_bindings.RescueFile.from_json(data)
+    assert rescue_file.deriver.mnemonic == deriver.mnemonic
+    restored = rescue_file.swaps[0]
+    assert restored.id == response.id
+
+    script = restored.script(rescue_file.deriver)
+    expected = boltz_client_bindings.BtcSwapScript.from_submarine_response(response, public_key)
+    assert script.script_pubkey() == expected.script_pubkey()
+
+
+def test_restore_web_app_rescue_file(server):
+    client = Client(server.url)
+    deriver = boltz_client_bindings.SwapKeyDeriver.from_mnemonic(boltz_client_bindings.SwapKeyDeriver.new_mnemonic())
+
+    _, refund_key = deriver.derive_keys(2)
+    invoice = server.create_invoice(50_000, hashlib.sha256(os.urandom(32)).digest())
+    submarine = client.create_submarine_swap("BTC", "BTC", invoice, refund_key, None)
+
+    _, claim_key = deriver.derive_keys(5)
+    preimage = deriver.derive_preimage(5)
+    reverse = client.create_reverse_swap("BTC", "BTC", preimage.sha256(), claim_key, 100_000)
+
+    # swaps of other keys are not restored
+    _, other_key = boltz_client_bindings.new_keys()
+    client.create_reverse_swap("BTC", "BTC", os.urandom(32), other_key, 100_000)
+
+    # the web app only writes the mnemonic
+    data = json.dumps({"mnemonic": deriver.mnemonic})
+    rescue_file = boltz_client_bindings.RescueFile.from_json(data, client)
+    swaps = {swap.id: swap for swap in rescue_file.swaps}
+    assert set(swaps) == {submarine.id, reverse.id}
+
+    script = swaps[submarine.id].script(rescue_file.deriver)
+    expected = boltz_client_bindings.BtcSwapScript.from_submarine_response(submarine, refund_key)
+    assert script.script_pubkey() == expected.script_pubkey()
+    assert swaps[submarine.id].private_key(rescue_file.deriver) == deriver.derive_keys(2)[0]
+
+    assert swaps[reverse.id].preimage == preimage.hex()
+    script = swaps[reverse.id].script(rescue_file.deriver)
+    assert script.lockup_address("regtest") == reverse.lockup_address
+
+
+def test_swap_store(server):
+    client = Client(server.url)
+    private_key, public_key = boltz_client_bindings.new_keys()
+    invoice = server.create_invoice(50_000, hashlib.sha256(os.urandom(32)).digest())
+    response = client.create_submarine_swap("BTC", "BTC", invoice, public_key, None)
+
+    store = boltz_client_bindings.SwapStore(":memory:")
+    swap = boltz_client_bindings.RescueSwap.from_submarine(response, "BTC", "BTC", private_key=private_key)
+    store.add(swap, SwapStatus.InvoiceSet)
+    assert [swap.id for swap in store.pending_swaps()] == [response.id]
+    assert store.swaps_needing_refund(response.timeout_block_height) == []
+
+    store.update_status(response.id, SwapStatus.TransactionMempool, "ab" * 32)
+    assert store.get(response.id).funding_txid == "ab" * 32
+    assert len(store.swaps_needing_refund(response.timeout_block_height)) == 1
+    assert store.swaps_needing_refund(response.timeout_block_height - 1) == []
+
+    store.update_status(response.id, SwapStatus.InvoiceFailedToPay)
+    assert store.pending_swaps() == []
+    assert len(store.swaps_by_status([SwapStatus.InvoiceFailedToPay])) == 1
+    assert [entry.status for entry in store.status_history(response.id)] == [
+        SwapStatus.InvoiceSet,
+        SwapStatus.TransactionMempool,
+        SwapStatus.InvoiceFailedToPay,
+    ]
+
+    store.set_refund_txid(response.id, "cd" * 32)
+    assert store.swaps_needing_refund() == []
+    assert store.get(response.id).rescue_swap().id == response.id
+
+
+def test_swap_manager(server):
+    client = Client(server.url)
+    secret_key, public_key = boltz_client_bindings.new_keys()
+    preimage = boltz_client_bindings.Preimage.new()
+    response = client.create_reverse_swap("BTC", "BTC", preimage.sha256(), public_key, 100_000)
+
+    events = []
+    # the backend serves Liquid, so the Bitcoin claim is broadcast through Boltz
+    backend = boltz_client_bindings.EsploraClient("L-BTC", "regtest", "http://127.0.0.1:1")
+    manager = boltz_client_bindings.SwapManager(
+        client,
+        backend,
+        boltz_client_bindings.SwapStore(":memory:"),
+        "regtest",
+        {"BTC": REFUND_ADDRESS},
+        fee_rate=2.0,
+        cooperative=False,
+        callback=events.append,
+    )
+    manager.add_swap(
+        boltz_client_bindings.RescueSwap.from_reverse(
+            response, "BTC", "BTC", preimage=preimage.hex(), private_key=secret_key
+        )
+    )
+
+    manager.start()
+    try:
+        server.fund_swap(response.id)
+        deadline = time.time() + 10
+        while not any(event.kind == "claimed" for event in events) and time.time() < deadline:
+            time.sleep(0.1)
+    finally:
+        manager.stop()
+
+    claimed = [event for event in events if event.kind == "claimed"]
+    assert len(claimed) == 1
+    assert client.get_transaction("BTC", claimed[0].transaction_id).startswith("02000000")
+
+
+def test_swap_manager_retries_claim(server):
+    client = Client(server.url)
+    secret_key, public_key = boltz_client_bindings.new_keys()
+    preimage = boltz_client_bindings.Preimage.new()
+    response = client.create_reverse_swap("BTC", "BTC", preimage.sha256(), public_key, 100_000)
+
+    store = boltz_client_bindings.SwapStore(":memory:")
+    backend = boltz_client_bindings.EsploraClient("L-BTC", "regtest", "http://127.0.0.1:1")
+    manager = boltz_client_bindings.SwapManager(
+        client, backend, store, "regtest", {"BTC": REFUND_ADDRESS}, fee_rate=2.0, cooperative=False
+    )
+    manager.add_swap(
+        boltz_client_bindings.RescueSwap.from_reverse(
+            response, "BTC", "BTC", preimage=preimage.hex(), private_key=secret_key
+        )
+    )
+
+    lockup_tx = server.fund_swap(response.id)
+    status = client.get_swap_status(response.id)
+    update = boltz_client_bindings.SwapUpdate(
+        response.id, SwapStatus.TransactionMempool, None, None, status.transaction_id, lockup_tx
+    )
+
+    server.set_response("POST", "/chain/BTC/transaction", 500, '{"error": "broadcast failed"}')
+    with pytest.raises(boltz_client_bindings.ApiError):
+        manager.process_update(update)
+    assert store.get(response.id).claim_txid is None
+
+    # the same status is delivered again and the claim retried
+    server.clear_responses()
+    manager.process_update(update)
+    assert store.get(response.id).claim_txid is not None
+
+
+def test_refund_scanner(server):
+    client = Client(server.url)
+    secret_key, public_key = boltz_client_bindings.new_keys()
+    invoice = server.create_invoice(50_000, hashlib.sha256(os.urandom(32)).digest())
+    submarine = client.create_submarine_swap("BTC", "BTC", invoice, public_key, None)
+    reverse = client.create_reverse_swap("BTC", "BTC", os.urandom(32), public_key, 100_000)
+
+    store = boltz_client_bindings.SwapStore(":memory:")
+    store.add(boltz_client_bindings.RescueSwap.from_submarine(submarine, "BTC", "BTC", private_key=secret_key))
+    store.add(boltz_client_bindings.RescueSwap.from_reverse(reverse, "BTC", "BTC", private_key=secret_key))
+
+    # the backend is unreachable, so the height of Boltz is used
+    backend = boltz_client_bindings.EsploraClient("BTC", "regtest", "http://127.0.0.1:1")
+    with pytest.raises(boltz_client_bindings.InvalidAddressError):
+        boltz_client_bindings.RefundScanner(backend, "main", REFUND_ADDRESS)
+
+    scanner = boltz_client_bindings.RefundScanner(backend, "regtest", REFUND_ADDRESS, client=client, dry_run=True)
+    results = {result.swap_id: result for result in scanner.scan(store.all_swaps())}
+    assert results[reverse.id].status == "skipped"
+    assert results[submarine.id].status == "pending"
+    assert results[submarine.id].current_height == 1000
+
+
+class EsploraStub(BaseHTTPRequestHandler):
+    """Serves the Esplora endpoints the refund scanner uses from class attributes."""
+
+    height = 1000
+    utxos = {}
+    transactions = {}
+
+    def do_GET(self):
+        if self.path == "/blocks/tip/height":
+            self.reply(str(self.height))
+        elif self.path.startswith("/scripthash/") and self.path.endswith("/utxo"):
+            self.reply(json.dumps(self.utxos.get(self.path.split("/")[2], [])))
+        elif self.path.startswith("/tx/") and self.path.endswith("/hex"):
+            self.reply(self.transactions[self.path.split("/")[2]])
+        else:
+            self.send_error(404)
+
+    def reply(self, body):
+        self.send_response(200)
+        self.end_headers()
+        self.wfile.write(body.encode())
+
+    def log_message(self, *args):
+        pass
+
+
+@pytest.fixture
+def esplora():
+    EsploraStub.height = 1000
+    EsploraStub.utxos = {}
+    EsploraStub.transactions = {}
+    httpd = HTTPServer(("127.0.0.1", 0), EsploraStub)
+    threading.Thread(target=httpd.serve_forever, daemon=True).start()
+    yield EsploraStub, f"http://127.0.0.1:{httpd.server_address[1]}"
+    httpd.shutdown()
+
+
+def test_refund_scanner_results(server, esplora):
+    stub, url = esplora
+    client = Client(server.url)
+    store = boltz_client_bindings.SwapStore(":memory:")
+
+    def submarine_swap(status, funded):
+        secret_key, public_key = boltz_client_bindings.new_keys()
+        invoice = server.create_invoice(50_000, hashlib.sha256(os.urandom(32)).digest())
+        response = client.create_submarine_swap("BTC", "BTC", invoice, public_key, None)
+        store.add(boltz_client_bindings.RescueSwap.from_submarine(response, "BTC", "BTC", private_key=secret_key), status)
+        if funded:
+            lockup_tx = server.fund_swap(response.id)
+            txid = client.get_swap_status(response.id).transaction_id
+            script = boltz_client_bindings.BtcSwapScript.from_submarine_response(response, public_key)
+            script_hash = hashlib.sha256(script.script_pubkey()).digest()[::-1].hex()
+            stub.utxos[script_hash] = [{"txid": txid, "vout": 0, "value": 50_000, "status": {"confirmed": False}}]
+            stub.transactions[txid] = lockup_tx
+        return response
+
+    unfunded = submarine_swap(SwapStatus.InvoiceFailedToPay, False)
+    pending = submarine_swap(SwapStatus.TransactionMempool, True)
+    failed = submarine_swap(SwapStatus.InvoiceFailedToPay, True)
+    completed = submarine_swap(SwapStatus.TransactionClaimed, True)
+
+    backend = boltz_client_bindings.EsploraClient("BTC", "regtest", url)
+    scanner = boltz_client_bindings.RefundScanner(backend, "regtest", REFUND_ADDRESS, client=client, fee_rate=2.0, dry_run=True)
+    results = {result.swap_id: result for result in scanner.scan(store.all_swaps())}
+    assert results[unfunded.id].status == "unfunded"
+    assert results[pending.id].status == "pending"
+    assert results[completed.id].status == "skipped"
+    assert results[failed.id].status == "would_refund"
+    assert results[failed.id].cooperative
+    assert results[failed.id].amount == 50_000
+
+    # with a client, expired swaps are still refunded cooperatively first
+    stub.height = pending.timeout_block_height
+    results = {result.swap_id: result for result in scanner.scan(store.all_swaps())}
+    assert results[pending.id].status == "would_refund"
+    assert results[pending.id].cooperative
+
+    # without a client, the swap that did not fail is refunded through the
+    # script path once it timed out
+    scanner = boltz_client_bindings.RefundScanner(backend, "regtest", REFUND_ADDRESS, fee_rate=2.0)
+    results = {result.swap_id: result for result in scanner.scan(store.all_swaps())}
+    assert results[pending.id].status == "signed"
+    assert not results[pending.id].cooperative
+    assert results[pending.id].transaction_hex.startswith("02000000")
+    assert results[completed.id].status == "skipped"
+
+
+def test_scripted_response(server):
+    client = Client(server.url, config=boltz_client_bindings.ClientConfig(retries=0))
+    server.set_response("GET", "/chain/heights", 503, '{"error": "maintenance"}')
+
+    with pytest.raises(boltz_client_bindings.ApiError) as err:
+        client.get_height()
+    assert err.value.status == 503
+
+
+def test_async_client(server):
+    client = boltz_client_bindings.AsyncClient(server.url, config=boltz_client_bindings.ClientConfig(retries=0))
+
+    async def run():
+        height, pairs = await asyncio.gather(client.get_height(), client.get_submarine_pairs())
+        assert height.btc == 1000
+        assert pairs.btc["BTC"].fees.percentage == 0.1
+
+        server.set_response("GET", "/chain/heights", 503, '{"error": "maintenance"}')
+        with pytest.raises(boltz_client_bindings.ApiError) as err:
+            await client.get_height()
+        assert err.value.status == 503
+
+    asyncio.run(run())
+
+
+def test_invalid_config(server):
+    for config in (
+        boltz_client_bindings.ClientConfig(read_timeout=-1.0),
+        boltz_client_bindings.ClientConfig(connect_timeout=float("nan")),
+        boltz_client_bindings.ClientConfig(retry_backoff=float("inf")),
+    ):
+        with pytest.raises(ValueError):
+            Client(server.url, config=config)
+
+
+def test_status_updates(server):
+    client = Client(server.url)
+    _, public_key = boltz_client_bindings.new_keys()
+    invoice = server.create_invoice(50_000, hashlib.sha256(os.urandom(32)).digest())
+    response = client.create_submarine_swap("BTC", "BTC", invoice, public_key, None)
+
+    stream = client.subscribe_swap_status([response.id])
+    assert stream.next_update().status == SwapStatus.InvoiceSet
+
+    server.push_status(response.id, SwapStatus.InvoicePaid)
+    assert stream.next_update().status == SwapStatus.InvoicePaid
+    stream.close()
@@ -0,0 +1,100 @@
+import pytest
+
+from boltz_client_bindings import BoltzError, SwapStateMachine, SwapStatus
+
+
+def test_parse_status():
+    assert SwapStatus.from_str("transaction.zeroconf.rejected") == SwapStatus.TransactionZeroconfRejected
+    assert SwapStatus.from_str("transaction.direct") == SwapStatus.TransactionDirect
+    assert SwapStatus.from_str("transaction.something.new") == SwapStatus.Unknown
+    assert str(SwapStatus.TransactionZeroconfRejected) == "transaction.zeroconf.rejected"
+
+
+def test_status_can_claim():
+    # the lockup of Boltz is claimable in reverse swaps, but ours in chain swaps is not
+    assert SwapStatus.TransactionMempool.can_claim("reverse")
+    assert not SwapStatus.TransactionMempool.can_claim("chain")
+    assert SwapStatus.TransactionServerConfirmed.can_claim("chain")
+    assert not SwapStatus.TransactionServerConfirmed.can_claim("reverse")
+    assert SwapStatus.TransactionClaimPending.can_claim("submarine")
+    assert not SwapStatus.TransactionMempool.can_claim("submarine")
+
+    with pytest.raises(ValueError):
+        SwapStatus.TransactionMempool.can_claim("swap")
+
+
+def test_submarine_transitions():
+    machine = SwapStateMachine("submarine")
+    assert machine.swap_type == "submarine"
+    assert not machine.can_claim()
+
+    machine.transition(SwapStatus.InvoiceSet)
+    machine.transition(SwapStatus.TransactionMempool)
+    machine.transition(SwapStatus.TransactionZeroconfRejected)
+    machine.transition(SwapStatus.TransactionClaimPending)
+    assert machine.can_claim()
+    machine.transition(SwapStatus.TransactionClaimed)
+    assert machine.is_final()
+    assert not machine.is_failed()
+
+    assert machine.history == [
+        SwapStatus.SwapCreated,
+        SwapStatus.InvoiceSet,
+        SwapStatus.TransactionMempool,
+        SwapStatus.TransactionZeroconfRejected,
+        SwapStatus.TransactionClaimPending,
+        SwapStatus.TransactionClaimed,
+    ]
+
+    with pytest.raises(BoltzError):
+        machine.transition(SwapStatus.InvoiceSet)
+    assert machine.status == SwapStatus.TransactionClaimed
+
+
+def test_submarine_refund():
+    machine = SwapStateMachine("submarine", SwapStatus.InvoiceSet)
+    machine.transition(SwapStatus.InvoiceFailedToPay)
+    # nothing was locked up, so there is nothing to refund
+    assert not machine.requires_refund()
+
+    machine = SwapStateMachine("submarine", SwapStatus.InvoiceSet)
+    machine.transition(SwapStatus.TransactionMempool)
+    machine.transition(SwapStatus.InvoiceFailedToPay)
+    assert machine.is_failed()
+    assert machine.requires_refund()
+
+
+def test_reverse_transitions():
+    machine = SwapStateMachine("reverse")
+    assert machine.can_transition(SwapStatus.TransactionDirect)
+    assert not machine.can_transition(SwapStatus.TransactionClaimPending)
+
+    machine.transition(SwapStatus.TransactionMempool)
+    assert machine.can_claim()
+    machine.transition(SwapStatus.InvoiceSettled)
+    assert not machine.can_claim()
+    assert not machine.requires_refund()
+
+    direct = SwapStateMachine("reverse", SwapStatus.MinerFeePaid)
+    direct.transition(SwapStatus.TransactionDirect)
+    assert direct.is_final()
+    assert not direct.is_failed()
+
+
+def test_chain_transitions():
+    machine = SwapStateMachine("chain")
+    machine.transition(SwapStatus.TransactionMempool)
+    assert not machine.can_claim()
+
+    machine.transition(SwapStatus.TransactionServerMempool)
+    assert machine.can_claim()
+    assert not machine.can_transition(SwapStatus.TransactionMempool)
+
+    machine = SwapStateMachine("chain", SwapStatus.TransactionConfirmed)
+    machine.transition(SwapStatus.TransactionLockupFailed)
+    assert machine.requires_refund()
+
+
+def test_invalid_swap_type():
+    with pytest.raises(ValueError):
+        SwapStateMachine("swap")
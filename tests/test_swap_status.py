@@ -0,0 +1,80 @@
+import asyncio
+import json
+import threading
+import time
+
+from websockets.sync.server import serve
+
+import boltz_client_bindings
+
+
+UPDATES = [
+    {"id": "swap1", "status": "invoice.set"},
+    {"id": "swap1", "status": "transaction.mempool", "transaction": {"id": "txid", "hex": "00"}},
+    {"id": "swap1", "transaction": "not an update"},
+    {"id": "swap1", "status": "transaction.claimed"},
+]
+
+
+def handler(websocket):
+    subscribe = json.loads(websocket.recv())
+    assert subscribe["op"] == "subscribe"
+    assert subscribe["channel"] == "swap.update"
+    websocket.send(json.dumps({"event": "subscribe", "channel": "swap.update", "args": subscribe["args"]}))
+    websocket.send(json.dumps({"event": "update", "channel": "swap.update", "args": UPDATES}))
+    websocket.close()
+
+
+def silent_handler(websocket):
+    websocket.recv()
+    for _ in websocket:
+        pass
+
+
+def start_server(handler=handler):
+    server = serve(handler, "127.0.0.1", 0)
+    threading.Thread(target=server.serve_forever, daemon=True).start()
+    return server, f"ws://127.0.0.1:{server.socket.getsockname()[1]}/v2/ws"
+
+
+def test_iterate_updates():
+    server, url = start_server()
+    stream = boltz_client_bindings.SwapStatusStream(url, ["swap1"])
+
+    statuses = [update.status for update in stream]
+    assert statuses == [
+        boltz_client_bindings.SwapStatus.InvoiceSet,
+        boltz_client_bindings.SwapStatus.TransactionMempool,
+        boltz_client_bindings.SwapStatus.TransactionClaimed,
+    ]
+    server.shutdown()
+
+
+def test_async_iterate_updates():
+    server, url = start_server()
+    stream = boltz_client_bindings.SwapStatusStream(url, ["swap1"])
+
+    async def collect():
+        return [update async for update in stream]
+
+    updates = asyncio.run(collect())
+    assert updates[1].transaction_id == "txid"
+    assert updates[1].transaction_hex == "00"
+    server.shutdown()
+
+
+def test_close_unblocks_read():
+    server, url = start_server(silent_handler)
+    stream = boltz_client_bindings.SwapStatusStream(url, ["swap1"])
+
+    updates = []
+    reader = threading.Thread(target=lambda: updates.append(stream.next_update()))
+    reader.start()
+    time.sleep(0.2)
+
+    stream.close()
+    reader.join(timeout=5)
+    assert not reader.is_alive()
+    assert updates == [None]
+    assert stream.next_update() is None
+    server.shutdown()